@@ -0,0 +1,187 @@
+//! Compares the sorted, binary searched maps against the linear scans they replaced, using
+//! generated almanacs that are far larger than the puzzle input.
+
+use std::collections::{HashMap, VecDeque};
+use std::ops::Range;
+use std::time::Instant;
+
+use crate::{overlap, part_1, part_2, split_range, Input, Map, MapEntry};
+
+const CATEGORIES: [&str; 8] = [
+    "seed",
+    "soil",
+    "fertilizer",
+    "water",
+    "light",
+    "temperature",
+    "humidity",
+    "location",
+];
+
+/// A xorshift generator, so that generated almanacs are the same on every run.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+}
+
+/// Generates an almanac with `entries` non-overlapping entries per map, leaving some gaps
+/// between them so that the identity fallback is exercised too.
+fn generate(rng: &mut Rng, entries: usize, seeds: usize) -> Input {
+    let mut maps = HashMap::<&'static str, Vec<Map>>::default();
+
+    for categories in CATEGORIES.windows(2) {
+        let mut src = 0;
+        let entries = (0..entries)
+            .map(|_| {
+                src += rng.below(100);
+                let len = 1 + rng.below(1_000);
+                let entry = MapEntry {
+                    dst: rng.below(1 << 40),
+                    src,
+                    len,
+                    line: 0,
+                };
+                src += len;
+                entry
+            })
+            .collect();
+
+        maps.entry(categories[0]).or_default().push(Map::new(
+            categories[0],
+            categories[1],
+            entries,
+            0,
+        ));
+    }
+
+    let limit = entries as u64 * 600;
+    let seeds = (0..seeds / 2)
+        .flat_map(|_| [rng.below(limit), 1 + rng.below(limit / 10_000)])
+        .collect();
+
+    Input { seeds, maps }
+}
+
+fn linear_map_value(map: &Map, value: u64) -> u64 {
+    map.entries
+        .iter()
+        .find(|entry| entry.src_range().contains(&value))
+        .map(|entry| entry.map_value(value))
+        .unwrap_or(value)
+}
+
+fn linear_map_values(map: &Map, values: Vec<Range<u64>>) -> Vec<Range<u64>> {
+    let mut inputs = VecDeque::from(values);
+    let mut outputs = Vec::new();
+
+    while let Some(next) = inputs.pop_front() {
+        let mapped = map.entries.iter().find_map(|entry| {
+            let src = entry.src_range();
+            if let Some(overlap) = overlap(&next, &src) {
+                if overlap != next {
+                    let (left, right) = split_range(&next, &overlap);
+                    if let Some(left) = left {
+                        inputs.push_front(left);
+                    }
+                    if let Some(right) = right {
+                        inputs.push_front(right);
+                    }
+                }
+
+                return Some(entry.map_range(&overlap));
+            };
+            None
+        });
+
+        outputs.push(mapped.unwrap_or(next));
+    }
+
+    outputs
+}
+
+fn linear_lowest_location(input: &Input) -> u64 {
+    let path = input.path("seed", "location").unwrap();
+
+    input
+        .seeds
+        .iter()
+        .map(|&seed| {
+            path.iter()
+                .fold(seed, |value, map| linear_map_value(map, value))
+        })
+        .min()
+        .unwrap()
+}
+
+fn linear_lowest_location_of_ranges(input: &Input) -> u64 {
+    let path = input.path("seed", "location").unwrap();
+
+    input
+        .seeds_as_ranges()
+        .flat_map(|seeds| {
+            path.iter()
+                .fold(vec![seeds], |values, map| linear_map_values(map, values))
+        })
+        .map(|range| range.start)
+        .min()
+        .unwrap()
+}
+
+fn time<T>(name: &str, f: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let result = f();
+    println!("  {name:<8} {:>10.1?}", start.elapsed());
+    result
+}
+
+pub fn run(entries: usize, seeds: usize) {
+    let input = generate(&mut Rng(0x2023_1205), entries, seeds);
+    println!("{entries} entries per map, {seeds} seeds");
+
+    println!("part 1:");
+    let linear = time("linear", || linear_lowest_location(&input));
+    let sorted = time("sorted", || part_1(&input));
+    assert_eq!(linear, sorted);
+
+    println!("part 2:");
+    let linear = time("linear", || linear_lowest_location_of_ranges(&input));
+    let sorted = time("sorted", || part_2(&input));
+    assert_eq!(linear, sorted);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{map_value, map_values};
+
+    #[test]
+    fn sorted_lookups_match_linear_scan() {
+        let input = generate(&mut Rng(42), 200, 2_000);
+
+        assert!(input.validate().is_empty());
+        assert_eq!(linear_lowest_location(&input), part_1(&input));
+        assert_eq!(linear_lowest_location_of_ranges(&input), part_2(&input));
+
+        for map in input.maps.values().flatten() {
+            for value in (0..150_000).step_by(37) {
+                assert_eq!(linear_map_value(map, value), map_value(map, value));
+            }
+
+            let mut linear = linear_map_values(map, vec![0..40_000, 90_000..150_000]);
+            let mut sorted = map_values(map, vec![0..40_000, 90_000..150_000]);
+            linear.sort_by_key(|range| range.start);
+            sorted.sort_by_key(|range| range.start);
+            assert_eq!(linear, sorted);
+        }
+    }
+}
@@ -1,15 +1,21 @@
-use std::collections::VecDeque;
+mod bench;
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
 use std::ops::Range;
 
 #[derive(Debug)]
 struct Input {
     seeds: Vec<u64>,
-    maps: Vec<Map>,
+    maps: HashMap<&'static str, Vec<Map>>,
 }
 
 #[derive(Debug)]
 struct Map {
+    src: &'static str,
+    dst: &'static str,
     entries: Vec<MapEntry>,
+    line: usize,
 }
 
 #[derive(Debug)]
@@ -17,45 +23,289 @@ struct MapEntry {
     dst: u64,
     src: u64,
     len: u64,
+    line: usize,
 }
 
-fn parse_seeds(input: &'static str) -> impl Iterator<Item = u64> {
-    let (seeds, _) = input.split_once('\n').unwrap();
-    let (_, seeds) = seeds.split_once(": ").unwrap();
-    seeds.split_whitespace().map(|s| s.parse().unwrap())
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct AlmanacError {
+    line: usize,
+    column: usize,
+    expected: &'static str,
+    found: String,
 }
 
-fn parse_map(input: &'static str) -> Map {
-    let (_, entries) = input.split_once(" map:\n").unwrap();
-    let entries = entries
-        .trim()
-        .split('\n')
-        .map(|entry| {
-            let mut values = entry.split_whitespace().map(|s| s.parse::<u64>().unwrap());
-            MapEntry {
-                dst: values.next().unwrap(),
-                src: values.next().unwrap(),
-                len: values.next().unwrap(),
-            }
-        })
-        .collect();
+impl fmt::Display for AlmanacError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: expected {}, found {}",
+            self.line, self.column, self.expected, self.found
+        )
+    }
+}
 
-    Map { entries }
+impl std::error::Error for AlmanacError {}
+
+/// Splits a line into whitespace separated tokens, along with the column each one starts at.
+fn tokens(line: &str) -> impl Iterator<Item = (usize, &str)> {
+    line.split_whitespace().map(move |token| {
+        let offset = token.as_ptr() as usize - line.as_ptr() as usize;
+        (line[..offset].chars().count() + 1, token)
+    })
 }
 
-fn parse(input: &'static str) -> Input {
-    let seeds = parse_seeds(input).collect();
+fn parse_numbers(line: usize, text: &str, count: Option<usize>) -> Result<Vec<u64>, AlmanacError> {
+    let mut numbers = Vec::new();
+
+    for (column, token) in tokens(text) {
+        if count == Some(numbers.len()) {
+            return Err(AlmanacError {
+                line,
+                column,
+                expected: "end of line",
+                found: format!("`{token}`"),
+            });
+        }
+
+        numbers.push(token.parse().map_err(|_| AlmanacError {
+            line,
+            column,
+            expected: "a number",
+            found: format!("`{token}`"),
+        })?);
+    }
+
+    if count.is_some_and(|count| numbers.len() < count) {
+        return Err(AlmanacError {
+            line,
+            column: text.chars().count() + 1,
+            expected: "a number",
+            found: "end of line".to_string(),
+        });
+    }
+
+    Ok(numbers)
+}
+
+fn parse_seeds(line: usize, text: &str) -> Result<Vec<u64>, AlmanacError> {
+    let Some(seeds) = text.strip_prefix("seeds:") else {
+        return Err(AlmanacError {
+            line,
+            column: 1,
+            expected: "`seeds:`",
+            found: format!("`{text}`"),
+        });
+    };
+
+    parse_numbers(line, seeds, None).map_err(|error| AlmanacError {
+        column: error.column + "seeds:".len(),
+        ..error
+    })
+}
+
+fn parse_entry(line: usize, text: &str) -> Result<MapEntry, AlmanacError> {
+    let values = parse_numbers(line, text, Some(3))?;
+
+    Ok(MapEntry {
+        dst: values[0],
+        src: values[1],
+        len: values[2],
+        line,
+    })
+}
+
+fn parse_map(
+    line: usize,
+    header: &'static str,
+    entries: Vec<MapEntry>,
+) -> Result<Map, AlmanacError> {
+    let Some(name) = header.trim().strip_suffix(" map:") else {
+        return Err(AlmanacError {
+            line,
+            column: 1,
+            expected: "a `<source>-to-<destination> map:` header",
+            found: format!("`{}`", header.trim()),
+        });
+    };
+    // A header without `-to-` is kept so that `Input::validate` can point at it.
+    let (src, dst) = name.split_once("-to-").unwrap_or((name, ""));
+
+    Ok(Map::new(src, dst, entries, line))
+}
+
+fn parse(input: &'static str) -> Result<Input, AlmanacError> {
+    let mut lines = input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim_end()))
+        .filter(|(_, line)| !line.is_empty())
+        .peekable();
+
+    let (line, seeds) = lines.next().ok_or(AlmanacError {
+        line: 1,
+        column: 1,
+        expected: "`seeds:`",
+        found: "end of input".to_string(),
+    })?;
+    let seeds = parse_seeds(line, seeds)?;
+
+    let mut maps = HashMap::<&'static str, Vec<Map>>::default();
+    while let Some((line, header)) = lines.next() {
+        let mut entries = Vec::new();
+        while let Some((line, entry)) =
+            lines.next_if(|(_, text)| text.trim_start().starts_with(|c: char| c.is_ascii_digit()))
+        {
+            entries.push(parse_entry(line, entry)?);
+        }
+
+        let map = parse_map(line, header, entries)?;
+        maps.entry(map.src).or_default().push(map);
+    }
+
+    Ok(Input { seeds, maps })
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+enum Problem {
+    OddSeedCount(usize),
+    MalformedHeader,
+    EmptyRange,
+    Overflow,
+    Overlap { line: usize },
+}
+
+/// A problem found by `Input::validate`, along with the line of the almanac it was found on.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+struct Diagnostic {
+    line: usize,
+    problem: Problem,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match self.problem {
+            Problem::OddSeedCount(count) => write!(
+                f,
+                "{count} seeds cannot be read as (start, length) pairs for part 2"
+            ),
+            Problem::MalformedHeader => {
+                write!(f, "expected a header like `<source>-to-<destination> map:`")
+            }
+            Problem::EmptyRange => write!(f, "entry has a length of zero"),
+            Problem::Overflow => write!(f, "range extends beyond {}", u64::MAX),
+            Problem::Overlap { line } => {
+                write!(f, "source range overlaps the entry on line {line}")
+            }
+        }
+    }
+}
 
-    let maps = input
-        .split("\n\n")
-        .skip(1)
-        .map(parse_map)
-        .collect::<Vec<_>>();
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum ConversionError {
+    UnknownCategory(String),
+    NoPath { from: String, to: String },
+    AmbiguousPath { from: String, to: String },
+}
 
-    Input { seeds, maps }
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownCategory(category) => write!(f, "unknown category `{category}`"),
+            Self::NoPath { from, to } => write!(f, "no maps lead from `{from}` to `{to}`"),
+            Self::AmbiguousPath { from, to } => {
+                write!(
+                    f,
+                    "more than one chain of maps leads from `{from}` to `{to}`"
+                )
+            }
+        }
+    }
 }
 
+impl std::error::Error for ConversionError {}
+
 impl Input {
+    fn has_category(&self, category: &str) -> bool {
+        self.maps
+            .values()
+            .flatten()
+            .any(|map| map.src == category || map.dst == category)
+    }
+
+    /// Finds the chain of maps that converts `from` into `to`, requiring it to be unique.
+    fn path(&self, from: &str, to: &str) -> Result<Vec<&Map>, ConversionError> {
+        for category in [from, to] {
+            if !self.has_category(category) {
+                return Err(ConversionError::UnknownCategory(category.to_string()));
+            }
+        }
+
+        let mut found = Vec::new();
+        self.find_paths(from, to, &mut vec![from], &mut Vec::new(), &mut found);
+
+        match found.len() {
+            0 => Err(ConversionError::NoPath {
+                from: from.to_string(),
+                to: to.to_string(),
+            }),
+            1 => Ok(found.remove(0)),
+            _ => Err(ConversionError::AmbiguousPath {
+                from: from.to_string(),
+                to: to.to_string(),
+            }),
+        }
+    }
+
+    fn find_paths<'a>(
+        &'a self,
+        from: &str,
+        to: &str,
+        visited: &mut Vec<&str>,
+        path: &mut Vec<&'a Map>,
+        found: &mut Vec<Vec<&'a Map>>,
+    ) {
+        if from == to {
+            found.push(path.clone());
+            return;
+        }
+
+        for map in self.maps.get(from).into_iter().flatten() {
+            // Two paths are enough to know the conversion is ambiguous.
+            if found.len() > 1 {
+                return;
+            }
+            if visited.contains(&map.dst) {
+                continue;
+            }
+
+            visited.push(map.dst);
+            path.push(map);
+            self.find_paths(map.dst, to, visited, path, found);
+            path.pop();
+            visited.pop();
+        }
+    }
+
+    fn convert(&self, from: &str, to: &str, value: u64) -> Result<u64, ConversionError> {
+        Ok(self
+            .path(from, to)?
+            .into_iter()
+            .fold(value, |value, map| map_value(map, value)))
+    }
+
+    fn convert_ranges(
+        &self,
+        from: &str,
+        to: &str,
+        values: Vec<Range<u64>>,
+    ) -> Result<Vec<Range<u64>>, ConversionError> {
+        Ok(self
+            .path(from, to)?
+            .into_iter()
+            .fold(values, |values, map| map_values(map, values)))
+    }
+
     fn seeds_as_ranges(&self) -> impl Iterator<Item = Range<u64>> + '_ {
         self.seeds
             .chunks(2)
@@ -63,6 +313,189 @@ impl Input {
     }
 }
 
+impl Input {
+    /// Checks the almanac for anything that would make the answers wrong or panic.
+    fn validate(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        if !self.seeds.len().is_multiple_of(2) {
+            diagnostics.push(Diagnostic {
+                line: 1,
+                problem: Problem::OddSeedCount(self.seeds.len()),
+            });
+        }
+        if self
+            .seeds
+            .chunks_exact(2)
+            .any(|chunk| chunk[0].checked_add(chunk[1]).is_none())
+        {
+            diagnostics.push(Diagnostic {
+                line: 1,
+                problem: Problem::Overflow,
+            });
+        }
+
+        for map in self.maps.values().flatten() {
+            if map.src.is_empty() || map.dst.is_empty() || map.src == map.dst {
+                diagnostics.push(Diagnostic {
+                    line: map.line,
+                    problem: Problem::MalformedHeader,
+                });
+            }
+
+            for entry in &map.entries {
+                if entry.len == 0 {
+                    diagnostics.push(Diagnostic {
+                        line: entry.line,
+                        problem: Problem::EmptyRange,
+                    });
+                }
+                if entry.src.checked_add(entry.len).is_none()
+                    || entry.dst.checked_add(entry.len).is_none()
+                {
+                    diagnostics.push(Diagnostic {
+                        line: entry.line,
+                        problem: Problem::Overflow,
+                    });
+                }
+            }
+
+            let ranges = map
+                .entries
+                .iter()
+                .filter(|entry| entry.len > 0 && entry.src.checked_add(entry.len).is_some())
+                .collect::<Vec<_>>();
+
+            for (i, a) in ranges.iter().enumerate() {
+                for b in &ranges[i + 1..] {
+                    if overlap(&a.src_range(), &b.src_range()).is_some() {
+                        diagnostics.push(Diagnostic {
+                            line: a.line.max(b.line),
+                            problem: Problem::Overlap {
+                                line: a.line.min(b.line),
+                            },
+                        });
+                    }
+                }
+            }
+        }
+
+        diagnostics.sort();
+        diagnostics
+    }
+}
+
+/// One stage of a seed's journey through the almanac.
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct TraceStep {
+    category: &'static str,
+    value: u64,
+    /// The line of the entry that produced `value`, or `None` if it was mapped to itself.
+    entry: Option<usize>,
+}
+
+/// How `map_values` handled one range taken from its queue. When only part of `input` is
+/// covered by an entry, `fragment` is that part and the remainder is queued to be mapped later.
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct RangeStep {
+    input: Range<u64>,
+    fragment: Range<u64>,
+    entry: Option<usize>,
+    output: Range<u64>,
+}
+
+#[derive(Debug)]
+struct RangeTrace {
+    src: &'static str,
+    dst: &'static str,
+    steps: Vec<RangeStep>,
+}
+
+impl Input {
+    fn trace(&self, seed: u64) -> Result<Vec<TraceStep>, ConversionError> {
+        let path = self.path("seed", "location")?;
+
+        let mut steps = vec![TraceStep {
+            category: "seed",
+            value: seed,
+            entry: None,
+        }];
+        for map in path {
+            let value = steps.last().unwrap().value;
+            let entry = find_entry(map, value);
+            steps.push(TraceStep {
+                category: map.dst,
+                value: entry.map_or(value, |entry| entry.map_value(value)),
+                entry: entry.map(|entry| entry.line),
+            });
+        }
+
+        Ok(steps)
+    }
+
+    fn trace_ranges(&self, seeds: Range<u64>) -> Result<Vec<RangeTrace>, ConversionError> {
+        let path = self.path("seed", "location")?;
+
+        let mut values = vec![seeds];
+        let mut traces = Vec::new();
+        for map in path {
+            let mut steps = Vec::new();
+            values = map_values_with(map, values, |step| steps.push(step));
+            traces.push(RangeTrace {
+                src: map.src,
+                dst: map.dst,
+                steps,
+            });
+        }
+
+        Ok(traces)
+    }
+}
+
+fn print_trace(steps: &[TraceStep]) {
+    let category_width = steps
+        .iter()
+        .map(|step| step.category.len())
+        .max()
+        .unwrap_or(0);
+    let value_width = steps
+        .iter()
+        .map(|step| step.value.to_string().len())
+        .max()
+        .unwrap_or(0);
+
+    for (i, step) in steps.iter().enumerate() {
+        let row = format!(
+            "{:<category_width$}  {:>value_width$}",
+            step.category, step.value
+        );
+        match step.entry {
+            _ if i == 0 => println!("{row}"),
+            Some(line) => println!("{row}  line {line}"),
+            None => println!("{row}  identity"),
+        }
+    }
+}
+
+fn print_range_trace(traces: &[RangeTrace]) {
+    for trace in traces {
+        println!("{}-to-{}:", trace.src, trace.dst);
+        for step in &trace.steps {
+            let source = step
+                .entry
+                .map_or("identity".to_string(), |line| format!("line {line}"));
+            if step.fragment == step.input {
+                println!("  {:?} -> {:?} ({source})", step.fragment, step.output);
+            } else {
+                println!(
+                    "  {:?} split, {:?} -> {:?} ({source})",
+                    step.input, step.fragment, step.output
+                );
+            }
+        }
+    }
+}
+
 impl MapEntry {
     fn src_range(&self) -> Range<u64> {
         self.src..self.src + self.len
@@ -86,10 +519,37 @@ impl MapEntry {
     }
 }
 
+impl Map {
+    fn new(src: &'static str, dst: &'static str, mut entries: Vec<MapEntry>, line: usize) -> Map {
+        // Sorted entries let lookups binary search rather than scan, assuming they don't overlap.
+        entries.sort_by_key(|entry| entry.src);
+
+        Map {
+            src,
+            dst,
+            entries,
+            line,
+        }
+    }
+
+    /// The first entry whose source range ends after `value`, which is the only one that can
+    /// contain it.
+    fn next_entry(&self, value: u64) -> Option<&MapEntry> {
+        let index = self
+            .entries
+            .partition_point(|entry| entry.src_range().end <= value);
+
+        self.entries[index..].iter().find(|entry| entry.len > 0)
+    }
+}
+
+fn find_entry(map: &Map, value: u64) -> Option<&MapEntry> {
+    map.next_entry(value)
+        .filter(|entry| entry.src_range().contains(&value))
+}
+
 fn map_value(map: &Map, value: u64) -> u64 {
-    map.entries
-        .iter()
-        .find(|entry| entry.src_range().contains(&value))
+    find_entry(map, value)
         .map(|entry| entry.map_value(value))
         .unwrap_or(value)
 }
@@ -115,11 +575,20 @@ fn split_range(a: &Range<u64>, b: &Range<u64>) -> (Option<Range<u64>>, Option<Ra
 }
 
 fn map_values(map: &Map, values: Vec<Range<u64>>) -> Vec<Range<u64>> {
+    map_values_with(map, values, |_| {})
+}
+
+/// Like `map_values`, but reports how each fragment of the input ranges was mapped.
+fn map_values_with(
+    map: &Map,
+    values: Vec<Range<u64>>,
+    mut on_step: impl FnMut(RangeStep),
+) -> Vec<Range<u64>> {
     let mut inputs = VecDeque::from(values);
     let mut outputs = Vec::new();
 
     while let Some(next) = inputs.pop_front() {
-        let mapped = map.entries.iter().find_map(|entry| {
+        let mapped = map.next_entry(next.start).and_then(|entry| {
             let src = entry.src_range();
             if let Some(overlap) = overlap(&next, &src) {
                 if overlap != next {
@@ -132,53 +601,130 @@ fn map_values(map: &Map, values: Vec<Range<u64>>) -> Vec<Range<u64>> {
                     }
                 }
 
-                return Some(entry.map_range(&overlap));
+                let output = entry.map_range(&overlap);
+                return Some((overlap, Some(entry.line), output));
             };
             None
         });
 
-        outputs.push(mapped.unwrap_or(next));
+        let (fragment, entry, output) = mapped.unwrap_or((next.clone(), None, next.clone()));
+        on_step(RangeStep {
+            input: next,
+            fragment,
+            entry,
+            output: output.clone(),
+        });
+        outputs.push(output);
     }
 
     outputs
 }
 
 fn part_1(input: &Input) -> u64 {
+    let path = input.path("seed", "location").unwrap();
+
     input
         .seeds
         .iter()
-        .map(|seed| {
-            input
-                .maps
-                .iter()
-                .fold(*seed, |value, map| map_value(map, value))
-        })
+        .map(|&seed| path.iter().fold(seed, |value, map| map_value(map, value)))
         .min()
         .expect("no minimum value found")
 }
 
 fn part_2(input: &Input) -> u64 {
+    let path = input.path("seed", "location").unwrap();
+
     input
         .seeds_as_ranges()
-        .map(|seeds| {
-            input
-                .maps
-                .iter()
+        .flat_map(|seeds| {
+            path.iter()
                 .fold(vec![seeds], |values, map| map_values(map, values))
-                .into_iter()
-                .map(|range| range.start)
-                .min()
-                .unwrap()
         })
+        .map(|range| range.start)
         .min()
         .expect("no minimum value found")
 }
 
+fn or_exit<T, E: std::fmt::Display>(result: Result<T, E>) -> T {
+    result.unwrap_or_else(|error| {
+        eprintln!("{error}");
+        std::process::exit(1);
+    })
+}
+
 fn main() {
-    let inputs = parse(include_str!("../input.txt"));
+    let inputs = or_exit(parse(include_str!("../input.txt")));
+
+    let diagnostics = inputs.validate();
+    if !diagnostics.is_empty() {
+        for diagnostic in diagnostics {
+            eprintln!("{diagnostic}");
+        }
+        std::process::exit(1);
+    }
 
-    println!("Part 1: {}", part_1(&inputs));
-    println!("Part 2: {}", part_2(&inputs));
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let numbers = |from: usize| {
+        args.get(from..)
+            .unwrap_or_default()
+            .iter()
+            .map(|arg| {
+                or_exit(
+                    arg.parse::<u64>()
+                        .map_err(|_| format!("`{arg}` is not a number")),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    let categories = || match &args[1..] {
+        [from, to, ..] => (from, to),
+        _ => or_exit(Err(
+            "expected a category to convert from and one to convert to",
+        )),
+    };
+
+    match args.first().map(String::as_str) {
+        Some("trace") => {
+            for seed in numbers(1) {
+                print_trace(&or_exit(inputs.trace(seed)));
+                println!();
+            }
+        }
+        Some("bench") => {
+            let numbers = numbers(1);
+            bench::run(
+                numbers.first().map_or(2_000, |&n| n as usize),
+                numbers.get(1).map_or(1_000_000, |&n| n as usize),
+            );
+        }
+        Some("convert") => {
+            let (from, to) = categories();
+            for value in &numbers(3) {
+                println!("{value} -> {}", or_exit(inputs.convert(from, to, *value)));
+            }
+        }
+        Some("convert-ranges") => {
+            let (from, to) = categories();
+            let ranges = numbers(3)
+                .chunks_exact(2)
+                .map(|range| range[0]..range[0] + range[1])
+                .collect::<Vec<_>>();
+            for range in or_exit(inputs.convert_ranges(from, to, ranges)) {
+                println!("{range:?}");
+            }
+        }
+        Some("trace-ranges") => {
+            for range in numbers(1).chunks_exact(2) {
+                println!("seeds {:?}", range[0]..range[0] + range[1]);
+                print_range_trace(&or_exit(inputs.trace_ranges(range[0]..range[0] + range[1])));
+                println!();
+            }
+        }
+        _ => {
+            println!("Part 1: {}", part_1(&inputs));
+            println!("Part 2: {}", part_2(&inputs));
+        }
+    }
 }
 
 #[cfg(test)]
@@ -213,4 +759,242 @@ mod test {
         assert_eq!(overlap(&d, &c), None);
         assert_eq!(overlap(&d, &e), Some(7..8));
     }
+
+    #[test]
+    fn example() {
+        let input = parse(include_str!("../example.txt")).unwrap();
+
+        assert_eq!(part_1(&input), 35);
+        assert_eq!(part_2(&input), 46);
+    }
+
+    #[test]
+    fn crlf_and_blank_lines() {
+        let input = include_str!("../example.txt")
+            .replace('\n', "  \r\n")
+            .replace("map:", "map:\r\n");
+        let input = parse(Box::leak(format!("\r\n{input}\r\n\r\n").into_boxed_str())).unwrap();
+
+        assert_eq!(part_1(&input), 35);
+        assert_eq!(part_2(&input), 46);
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error = |input| parse(input).unwrap_err();
+
+        assert_eq!(
+            error(""),
+            AlmanacError {
+                line: 1,
+                column: 1,
+                expected: "`seeds:`",
+                found: "end of input".to_string(),
+            }
+        );
+        assert_eq!(
+            error("seeds: 79 1x\n"),
+            AlmanacError {
+                line: 1,
+                column: 11,
+                expected: "a number",
+                found: "`1x`".to_string(),
+            }
+        );
+        assert_eq!(
+            error("seeds: 79 14\n\nseed-to-soil map:\n50 98\n"),
+            AlmanacError {
+                line: 4,
+                column: 6,
+                expected: "a number",
+                found: "end of line".to_string(),
+            }
+        );
+        assert_eq!(
+            error("seeds: 79 14\n\nseed-to-soil map:\n50 98 2 7\n"),
+            AlmanacError {
+                line: 4,
+                column: 9,
+                expected: "end of line",
+                found: "`7`".to_string(),
+            }
+        );
+        assert_eq!(
+            error("seeds: 79 14\n\nseed-to-soil:\n50 98 2\n").to_string(),
+            "line 3, column 1: expected a `<source>-to-<destination> map:` header, \
+             found `seed-to-soil:`"
+        );
+    }
+
+    #[test]
+    fn convert_between_categories() {
+        let almanac = parse(include_str!("../example.txt")).unwrap();
+
+        assert_eq!(almanac.convert("seed", "soil", 79), Ok(81));
+        assert_eq!(almanac.convert("seed", "location", 79), Ok(82));
+        assert_eq!(almanac.convert("soil", "humidity", 81), Ok(78));
+        assert_eq!(almanac.convert("water", "water", 81), Ok(81));
+        assert_eq!(
+            almanac.convert_ranges("seed", "soil", vec![79..93, 55..68]),
+            Ok(vec![81..95, 57..70])
+        );
+    }
+
+    #[test]
+    fn conversion_errors() {
+        let almanac = parse(include_str!("../example.txt")).unwrap();
+
+        assert_eq!(
+            almanac.convert("location", "seed", 82),
+            Err(ConversionError::NoPath {
+                from: "location".to_string(),
+                to: "seed".to_string()
+            })
+        );
+        assert_eq!(
+            almanac.convert("seed", "spaceship", 82),
+            Err(ConversionError::UnknownCategory("spaceship".to_string()))
+        );
+
+        let shortcut = include_str!("../example.txt").to_string() + "\nseed-to-water map:\n0 0 1\n";
+        let almanac = parse(Box::leak(shortcut.into_boxed_str())).unwrap();
+
+        assert_eq!(
+            almanac.convert("seed", "light", 79),
+            Err(ConversionError::AmbiguousPath {
+                from: "seed".to_string(),
+                to: "light".to_string()
+            })
+        );
+        assert_eq!(almanac.convert("water", "light", 81), Ok(74));
+    }
+
+    #[test]
+    fn trace() {
+        let almanac = parse(include_str!("../example.txt")).unwrap();
+
+        let trace = almanac.trace(79).unwrap();
+        assert_eq!(
+            trace
+                .iter()
+                .map(|step| (step.category, step.value))
+                .collect::<Vec<_>>(),
+            vec![
+                ("seed", 79),
+                ("soil", 81),
+                ("fertilizer", 81),
+                ("water", 81),
+                ("light", 74),
+                ("temperature", 78),
+                ("humidity", 78),
+                ("location", 82),
+            ]
+        );
+        assert_eq!(trace[1].entry, Some(5));
+        assert_eq!(trace[2].entry, None);
+    }
+
+    #[test]
+    fn trace_ranges() {
+        let almanac = parse(include_str!("../example.txt")).unwrap();
+
+        let traces = almanac.trace_ranges(79..93).unwrap();
+        assert_eq!(traces.len(), 7);
+        assert_eq!(
+            traces[0].steps,
+            vec![RangeStep {
+                input: 79..93,
+                fragment: 79..93,
+                entry: Some(5),
+                output: 81..95,
+            }]
+        );
+        // The light-to-temperature map splits the range in two.
+        assert_eq!(
+            traces[4].steps,
+            vec![
+                RangeStep {
+                    input: 74..88,
+                    fragment: 74..77,
+                    entry: Some(25),
+                    output: 78..81,
+                },
+                RangeStep {
+                    input: 77..88,
+                    fragment: 77..88,
+                    entry: Some(23),
+                    output: 45..56,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn validation() {
+        assert_eq!(
+            parse(include_str!("../example.txt")).unwrap().validate(),
+            vec![]
+        );
+
+        let almanac = parse(
+            "seeds: 79 14 55\n\
+             \n\
+             seed-to-soil map:\n\
+             50 98 2\n\
+             52 50 48\n\
+             0 90 10\n\
+             \n\
+             soil map:\n\
+             0 18446744073709551615 2\n\
+             7 7 0\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            almanac.validate(),
+            vec![
+                Diagnostic {
+                    line: 1,
+                    problem: Problem::OddSeedCount(3)
+                },
+                Diagnostic {
+                    line: 6,
+                    problem: Problem::Overlap { line: 4 }
+                },
+                Diagnostic {
+                    line: 6,
+                    problem: Problem::Overlap { line: 5 }
+                },
+                Diagnostic {
+                    line: 8,
+                    problem: Problem::MalformedHeader
+                },
+                Diagnostic {
+                    line: 9,
+                    problem: Problem::Overflow
+                },
+                Diagnostic {
+                    line: 10,
+                    problem: Problem::EmptyRange
+                },
+            ]
+        );
+
+        // An unpaired last seed has no range length to overflow with.
+        let almanac = parse(
+            "seeds: 1 2 10000000000000000000
+
+seed-to-soil map:
+0 0 1
+",
+        )
+        .unwrap();
+        assert_eq!(
+            almanac.validate(),
+            vec![Diagnostic {
+                line: 1,
+                problem: Problem::OddSeedCount(3)
+            }]
+        );
+    }
 }
@@ -0,0 +1,140 @@
+//! Times classifying and scoring generated hands with the lookup table `Classifier` against the
+//! implementation it replaced, which counted cards into a `HashMap` inside the sort comparator.
+
+use std::{collections::HashMap, fs, ops::AddAssign, time::Instant};
+
+use crate::{classifier::Classifier, hands, rng::Rng, Card, Hand, RuleSet, Type, CARDS};
+
+/// Generates `count` random five-card hands in the puzzle input format, with bids of up to
+/// `max_bid`.
+pub fn generate(rng: &mut Rng, count: usize, max_bid: u64) -> String {
+    (0..count)
+        .map(|_| {
+            let cards = (0..5)
+                .map(|_| char::from(CARDS[rng.below(CARDS.len() as u64) as usize]))
+                .collect::<String>();
+            format!("{cards} {}\n", 1 + rng.below(max_bid))
+        })
+        .collect()
+}
+
+fn hash_map_hand_type(cards: &[Card], wildcards: &[Card]) -> Type {
+    let counts: HashMap<_, _> = cards.iter().filter(|card| !wildcards.contains(card)).fold(
+        HashMap::default(),
+        |mut count, card| {
+            count.entry(card).or_insert(0).add_assign(1);
+            count
+        },
+    );
+
+    let jokers = cards.iter().filter(|card| wildcards.contains(card)).count();
+
+    if jokers == 5 {
+        return Type::FiveOfAKind;
+    }
+
+    match counts.len() {
+        1 => Type::FiveOfAKind,
+        2 => {
+            if counts.values().any(|&count| count + jokers == 4) {
+                Type::FourOfAKind
+            } else {
+                Type::FullHouse
+            }
+        }
+        3 => {
+            if counts.values().any(|&count| count + jokers == 3) {
+                Type::ThreeOfAKind
+            } else {
+                Type::TwoPair
+            }
+        }
+        4 => {
+            if counts.values().any(|&count| count + jokers == 2) {
+                Type::OnePair
+            } else {
+                Type::HighCard
+            }
+        }
+        5 => Type::HighCard,
+        _ => unreachable!("invalid hand"),
+    }
+}
+
+fn hash_map_winnings(mut hands: Vec<Hand>, rules: &RuleSet) -> u64 {
+    let key = |hand: &Hand| {
+        let strengths = hand
+            .cards
+            .iter()
+            .map(|&card| rules.strength(card))
+            .collect::<Vec<_>>();
+        (hash_map_hand_type(&hand.cards, &rules.wildcards), strengths)
+    };
+
+    hands.sort_by_key(key);
+    hands
+        .into_iter()
+        .enumerate()
+        .map(|(rank, play)| (rank + 1) as u64 * play.bid)
+        .sum()
+}
+
+fn time<T>(name: &str, hands: usize, f: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let result = f();
+    let elapsed = start.elapsed();
+    if hands > 0 {
+        println!(
+            "  {name:<10} {elapsed:>10.1?} {:>8.1}M hands/s",
+            hands as f64 / elapsed.as_secs_f64() / 1e6
+        );
+    } else {
+        println!("  {name:<10} {elapsed:>10.1?}");
+    }
+    result
+}
+
+/// Benchmarks `count` generated hands, also writing them to `path` if one is given.
+pub fn run(count: usize, path: Option<&str>) {
+    let input = generate(&mut Rng::new(0x2023_1207), count, 1_000);
+    if let Some(path) = path {
+        fs::write(path, &input).expect("failed to write hands");
+    }
+    let hands = hands(&input).unwrap();
+    let rules = RuleSet::jokers();
+
+    println!("{count} hands");
+    println!("classify:");
+    let expected = time("hash map", count, || {
+        hands
+            .iter()
+            .map(|hand| hash_map_hand_type(&hand.cards, &rules.wildcards))
+            .collect::<Vec<_>>()
+    });
+    let actual = time("counts", count, || {
+        let classifier = Classifier::new(&rules);
+        hands
+            .iter()
+            .map(|hand| classifier.hand_type(&hand.cards))
+            .collect::<Vec<_>>()
+    });
+    assert_eq!(expected, actual);
+    // Building the table is part of the cost, since every run builds it afresh.
+    let actual = time("table", count, || {
+        let classifier = Classifier::with_table(&rules);
+        hands
+            .iter()
+            .map(|hand| classifier.hand_type(&hand.cards))
+            .collect::<Vec<_>>()
+    });
+    assert_eq!(expected, actual);
+
+    println!("winnings:");
+    let expected = time("hash map", count, || {
+        hash_map_winnings(hands.clone(), &rules)
+    });
+    let actual = time("classifier", count, || {
+        crate::winnings(hands.clone(), &rules).unwrap()
+    });
+    assert_eq!(expected, actual);
+}
@@ -0,0 +1,150 @@
+//! Precomputed tables for classifying and ordering hands quickly under a `RuleSet`.
+
+use crate::{hand_type, Card, RuleSet, TieBreak, Type, CARDS};
+
+/// The longest hand whose sort key fits in a `u64`: four bits per card below the type.
+pub const MAX_CARDS: usize = 15;
+
+/// How many entries the five-card table has. Building it classifies every one of them, so it
+/// only pays for itself when classifying at least as many hands.
+pub const FIVE_CARD_HANDS: usize = CARDS.len().pow(5);
+
+pub struct Classifier {
+    /// Each card's strength under the rule set, from 1 so that no card packs to zero.
+    strengths: [u8; CARDS.len()],
+    tie_break: TieBreak,
+    /// The type of every five-card hand, indexed by reading the cards as a base 13 number, if
+    /// the table was built.
+    five_card_types: Option<Vec<Type>>,
+    wildcards: Vec<Card>,
+}
+
+fn five_card_index(cards: &[Card]) -> usize {
+    cards
+        .iter()
+        .fold(0, |index, &card| index * CARDS.len() + card as usize)
+}
+
+fn five_card_hand(mut index: usize) -> [Card; 5] {
+    let mut cards = [CARDS[0]; 5];
+    for card in cards.iter_mut().rev() {
+        *card = CARDS[index % CARDS.len()];
+        index /= CARDS.len();
+    }
+    cards
+}
+
+impl Classifier {
+    /// A classifier that counts each hand's cards to find its type.
+    pub fn new(rules: &RuleSet) -> Self {
+        Self {
+            strengths: CARDS.map(|card| rules.strength(card) as u8 + 1),
+            tie_break: rules.tie_break,
+            five_card_types: None,
+            wildcards: rules.wildcards.clone(),
+        }
+    }
+
+    /// A classifier that looks five-card hands up in a table built up front.
+    pub fn with_table(rules: &RuleSet) -> Self {
+        let five_card_types = (0..FIVE_CARD_HANDS)
+            .map(|index| hand_type(&five_card_hand(index), &rules.wildcards))
+            .collect();
+
+        Self {
+            five_card_types: Some(five_card_types),
+            ..Self::new(rules)
+        }
+    }
+
+    /// A classifier for sorting `hands` hands, with the table only if it pays for itself.
+    pub fn for_hands(rules: &RuleSet, hands: usize) -> Self {
+        if hands >= FIVE_CARD_HANDS {
+            Self::with_table(rules)
+        } else {
+            Self::new(rules)
+        }
+    }
+
+    pub fn hand_type(&self, cards: &[Card]) -> Type {
+        match &self.five_card_types {
+            Some(types) if cards.len() == 5 => types[five_card_index(cards)],
+            _ => hand_type(cards, &self.wildcards),
+        }
+    }
+
+    /// A key that orders hands the same way as `RuleSet::sort_key`, packed into a single integer
+    /// so that sorting doesn't allocate.
+    pub fn sort_key(&self, cards: &[Card]) -> u64 {
+        assert!(cards.len() <= MAX_CARDS, "too many cards to pack");
+
+        let mut strengths = [0; MAX_CARDS];
+        for (strength, &card) in strengths.iter_mut().zip(cards) {
+            *strength = self.strengths[card as usize];
+        }
+        if self.tie_break == TieBreak::HighestFirst {
+            strengths.sort_unstable_by(|a, b| b.cmp(a));
+        }
+
+        let packed = strengths
+            .iter()
+            .fold(0, |key, &strength| key << 4 | u64::from(strength));
+        (self.hand_type(cards) as u64) << (4 * MAX_CARDS) | packed
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rng::Rng;
+
+    #[test]
+    fn matches_hand_type() {
+        for rules in [RuleSet::standard(), RuleSet::jokers()] {
+            let classifier = Classifier::with_table(&rules);
+            let types = classifier.five_card_types.as_ref().unwrap();
+            for (index, &hand_type) in types.iter().enumerate() {
+                let cards = five_card_hand(index);
+                assert_eq!(five_card_index(&cards), index);
+                assert_eq!(hand_type, rules.hand_type(&cards));
+            }
+        }
+    }
+
+    #[test]
+    fn matches_sort_key() {
+        let mut rng = Rng::new(36);
+        let aces_low_highest_first = RuleSet {
+            ranking: [&[Card::Ace][..], &CARDS[..12]].concat(),
+            wildcards: vec![Card::Two, Card::Three],
+            tie_break: TieBreak::HighestFirst,
+        };
+
+        for rules in [
+            RuleSet::standard(),
+            RuleSet::jokers(),
+            aces_low_highest_first,
+        ] {
+            let classifiers = [Classifier::new(&rules), Classifier::with_table(&rules)];
+            let mut hands = (0..2_000)
+                .map(|_| {
+                    let len = 1 + rng.below(MAX_CARDS as u64) as usize;
+                    (0..len)
+                        .map(|_| CARDS[rng.below(CARDS.len() as u64) as usize])
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+
+            hands.sort_by_key(|hand| rules.sort_key(hand));
+            for classifier in &classifiers {
+                for pair in hands.windows(2) {
+                    assert!(classifier.sort_key(&pair[0]) <= classifier.sort_key(&pair[1]));
+                    assert_eq!(
+                        rules.sort_key(&pair[0]) == rules.sort_key(&pair[1]),
+                        classifier.sort_key(&pair[0]) == classifier.sort_key(&pair[1])
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,347 @@
+//! How often each player wins a Hold'em hand when some of the cards are still to come.
+//!
+//! The missing cards are dealt every possible way when there are few enough deals, and by a
+//! seeded Monte Carlo simulation otherwise.
+
+use std::fmt;
+
+use crate::holdem::{check_deal, showdown, DealError};
+use crate::poker::{deck, PokerCard};
+use crate::rng::Rng;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Estimate {
+    pub probability: f64,
+    /// Zero when the probability was found exactly.
+    pub standard_error: f64,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Equity {
+    pub win: Estimate,
+    pub tie: Estimate,
+    pub lose: Estimate,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub players: Vec<Equity>,
+    pub deals: u64,
+    pub exact: bool,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Settings {
+    /// Deal every possible way when there are at most this many deals.
+    pub exact_limit: u64,
+    pub trials: u64,
+    pub seed: u64,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            exact_limit: 2_000_000,
+            trials: 100_000,
+            seed: 7,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum EquityError {
+    Deal(DealError),
+    /// More cards are missing than are left in the deck to deal them from.
+    NotEnoughCards {
+        missing: usize,
+        remaining: usize,
+    },
+}
+
+impl fmt::Display for EquityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EquityError::Deal(error) => write!(f, "{error}"),
+            EquityError::NotEnoughCards { missing, remaining } => write!(
+                f,
+                "{missing} cards are missing, but only {remaining} are left in the deck"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for EquityError {}
+
+impl From<DealError> for EquityError {
+    fn from(error: DealError) -> Self {
+        EquityError::Deal(error)
+    }
+}
+
+#[derive(Debug, Default, Copy, Clone)]
+struct Tally {
+    wins: u64,
+    ties: u64,
+    losses: u64,
+}
+
+/// Calculates each player's equity given whatever hole cards (up to two each) and board cards
+/// (up to five) are known.
+pub fn equity(
+    players: &[Vec<PokerCard>],
+    board: &[PokerCard],
+    settings: &Settings,
+) -> Result<Report, EquityError> {
+    let holes = players.iter().map(Vec::as_slice).collect::<Vec<_>>();
+    check_deal(&holes, board)?;
+
+    let known = players
+        .iter()
+        .flatten()
+        .chain(board)
+        .copied()
+        .collect::<Vec<_>>();
+    let remaining = deck()
+        .filter(|card| !known.contains(card))
+        .collect::<Vec<_>>();
+
+    // The missing cards are dealt to each player in turn, then to the board.
+    let groups = players
+        .iter()
+        .map(|hole| 2 - hole.len())
+        .chain([5 - board.len()])
+        .collect::<Vec<_>>();
+    let missing = groups.iter().sum::<usize>();
+    if missing > remaining.len() {
+        return Err(EquityError::NotEnoughCards {
+            missing,
+            remaining: remaining.len(),
+        });
+    }
+
+    let mut tallies = vec![Tally::default(); players.len()];
+    let mut record = |dealt: &[PokerCard]| {
+        let mut dealt = dealt.iter().copied();
+        let holes = players
+            .iter()
+            .map(|hole| {
+                let mut hole = hole.clone();
+                hole.extend(dealt.by_ref().take(2 - hole.len()));
+                [hole[0], hole[1]]
+            })
+            .collect::<Vec<_>>();
+        let board = board.iter().copied().chain(dealt).collect::<Vec<_>>();
+
+        let winners = showdown(&holes, &board);
+        for (player, tally) in tallies.iter_mut().enumerate() {
+            match winners.contains(&player) {
+                true if winners.len() == 1 => tally.wins += 1,
+                true => tally.ties += 1,
+                false => tally.losses += 1,
+            }
+        }
+    };
+
+    let exact = deals(remaining.len(), &groups) <= u128::from(settings.exact_limit);
+    let deals = if exact {
+        let mut used = vec![false; remaining.len()];
+        let mut dealt = Vec::new();
+        deal_every_way(&remaining, &mut used, &groups, 0, &mut dealt, &mut record)
+    } else {
+        let mut rng = Rng::new(settings.seed);
+        let mut remaining = remaining;
+        for _ in 0..settings.trials {
+            // A partial Fisher-Yates shuffle moves a random selection of cards to the front.
+            for i in 0..missing {
+                let j = i + rng.below((remaining.len() - i) as u64) as usize;
+                remaining.swap(i, j);
+            }
+            record(&remaining[..missing]);
+        }
+        settings.trials
+    };
+
+    let estimate = |count: u64| {
+        let probability = count as f64 / deals as f64;
+        let standard_error = if exact {
+            0.0
+        } else {
+            (probability * (1.0 - probability) / deals as f64).sqrt()
+        };
+        Estimate {
+            probability,
+            standard_error,
+        }
+    };
+
+    Ok(Report {
+        players: tallies
+            .into_iter()
+            .map(|tally| Equity {
+                win: estimate(tally.wins),
+                tie: estimate(tally.ties),
+                lose: estimate(tally.losses),
+            })
+            .collect(),
+        deals,
+        exact,
+    })
+}
+
+/// The number of ways to deal groups of cards of the given sizes from `cards` cards, where the
+/// order within a group doesn't matter.
+fn deals(mut cards: usize, groups: &[usize]) -> u128 {
+    let mut total = 1u128;
+    for &group in groups {
+        for i in 0..group {
+            total = total.saturating_mul((cards - i) as u128) / (i as u128 + 1);
+        }
+        cards -= group;
+    }
+    total
+}
+
+/// Deals the remaining cards in every way, choosing each group's cards in increasing index order
+/// so that no deal is visited twice. Returns the number of deals visited.
+fn deal_every_way(
+    cards: &[PokerCard],
+    used: &mut [bool],
+    groups: &[usize],
+    from: usize,
+    dealt: &mut Vec<PokerCard>,
+    visit: &mut impl FnMut(&[PokerCard]),
+) -> u64 {
+    let Some((&group, rest)) = groups.split_first() else {
+        visit(dealt);
+        return 1;
+    };
+    if group == 0 {
+        return deal_every_way(cards, used, rest, 0, dealt, visit);
+    }
+
+    let mut count = 0;
+    let groups = [&[group - 1], rest].concat();
+    for i in from..cards.len() {
+        if used[i] {
+            continue;
+        }
+
+        used[i] = true;
+        dealt.push(cards[i]);
+        count += deal_every_way(cards, used, &groups, i + 1, dealt, visit);
+        dealt.pop();
+        used[i] = false;
+    }
+    count
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::poker::parse_cards;
+
+    #[test]
+    fn exact_on_the_turn() {
+        let report = equity(
+            &[parse_cards("As Qc").unwrap(), parse_cards("Kc Qs").unwrap()],
+            &parse_cards("Ah Kd 7c 2s").unwrap(),
+            &Settings::default(),
+        )
+        .unwrap();
+
+        assert!(report.exact);
+        assert_eq!(report.deals, 44);
+        // Only the two remaining kings save the player with second pair.
+        assert_eq!(report.players[0].win.probability, 42.0 / 44.0);
+        assert_eq!(report.players[1].win.probability, 2.0 / 44.0);
+        assert_eq!(report.players[1].lose.standard_error, 0.0);
+    }
+
+    #[test]
+    fn exact_ties() {
+        let report = equity(
+            &[parse_cards("As Kc").unwrap(), parse_cards("Ad Kh").unwrap()],
+            &parse_cards("2c 7d 9h").unwrap(),
+            &Settings::default(),
+        )
+        .unwrap();
+
+        assert_eq!(report.deals, 45 * 44 / 2);
+        for player in &report.players {
+            assert_eq!(player.win.probability, player.lose.probability);
+            assert!(player.tie.probability > 0.9);
+        }
+    }
+
+    #[test]
+    fn unknown_hole_cards() {
+        let report = equity(
+            &[parse_cards("Ac Kc").unwrap(), vec![]],
+            &parse_cards("Ah Ad As 2c").unwrap(),
+            &Settings::default(),
+        )
+        .unwrap();
+
+        assert!(report.exact);
+        assert_eq!(report.deals, 46 * 45 / 2 * 44);
+        assert_eq!(report.players[0].win.probability, 1.0);
+    }
+
+    #[test]
+    fn monte_carlo() {
+        let settings = Settings {
+            exact_limit: 0,
+            trials: 20_000,
+            seed: 2023,
+        };
+        let players = [parse_cards("Ah Ad").unwrap(), parse_cards("Kh Kd").unwrap()];
+
+        let report = equity(&players, &[], &settings).unwrap();
+        assert!(!report.exact);
+        assert_eq!(report.deals, 20_000);
+        assert_eq!(Ok(report.clone()), equity(&players, &[], &settings));
+
+        // Aces beat kings about 82% of the time.
+        let win = report.players[0].win;
+        assert!(win.standard_error > 0.0);
+        assert!((win.probability - 0.8236).abs() < 4.0 * win.standard_error);
+
+        for player in &report.players {
+            let total = player.win.probability + player.tie.probability + player.lose.probability;
+            assert!((total - 1.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn not_enough_cards() {
+        // Unknown hole cards for 24 players and the whole board need 53 cards.
+        assert_eq!(
+            equity(&vec![vec![]; 24], &[], &Settings::default()),
+            Err(EquityError::NotEnoughCards {
+                missing: 53,
+                remaining: 52
+            })
+        );
+    }
+
+    #[test]
+    fn bad_deals() {
+        let hands = [
+            parse_cards("Ah Kd 7c").unwrap(),
+            parse_cards("As Qc").unwrap(),
+        ];
+        assert_eq!(
+            equity(&hands, &[], &Settings::default()),
+            Err(EquityError::Deal(DealError::TooManyHoleCards {
+                player: 0,
+                count: 3
+            }))
+        );
+
+        let board = parse_cards("As").unwrap();
+        assert!(matches!(
+            equity(&hands[1..], &board, &Settings::default()),
+            Err(EquityError::Deal(DealError::DuplicateCard(_)))
+        ));
+    }
+}
@@ -0,0 +1,235 @@
+//! Texas Hold'em showdowns, where each player makes their best five-card hand from their two
+//! hole cards and the shared board.
+
+use std::{
+    cmp::{Ordering, Reverse},
+    fmt,
+};
+
+use crate::poker::{evaluate, PokerCard, Value};
+
+/// The strongest five-card hand a player can make, and the cards that make it.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Best {
+    pub value: Value,
+    pub cards: [PokerCard; 5],
+}
+
+/// Why a deal isn't one a real game could have.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum DealError {
+    TooManyBoardCards(usize),
+    /// Players are numbered from zero.
+    TooManyHoleCards {
+        player: usize,
+        count: usize,
+    },
+    DuplicateCard(PokerCard),
+}
+
+impl fmt::Display for DealError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DealError::TooManyBoardCards(count) => {
+                write!(f, "the board has {count} cards, but at most five")
+            }
+            DealError::TooManyHoleCards { player, count } => write!(
+                f,
+                "player {} has {count} hole cards, but at most two",
+                player + 1
+            ),
+            DealError::DuplicateCard(card) => write!(f, "{card} was dealt more than once"),
+        }
+    }
+}
+
+impl std::error::Error for DealError {}
+
+/// Checks that the board and each player's hole cards aren't too many, and that no card was
+/// dealt twice.
+pub fn check_deal(players: &[&[PokerCard]], board: &[PokerCard]) -> Result<(), DealError> {
+    if board.len() > 5 {
+        return Err(DealError::TooManyBoardCards(board.len()));
+    }
+    if let Some((player, hole)) = players.iter().enumerate().find(|(_, hole)| hole.len() > 2) {
+        return Err(DealError::TooManyHoleCards {
+            player,
+            count: hole.len(),
+        });
+    }
+
+    let mut seen = Vec::new();
+    for &card in players.iter().copied().flatten().chain(board) {
+        if seen.contains(&card) {
+            return Err(DealError::DuplicateCard(card));
+        }
+        seen.push(card);
+    }
+    Ok(())
+}
+
+/// Finds the best hand from two hole cards and up to five board cards, or `None` if there are
+/// fewer than five cards in total.
+pub fn best_hand(hole: [PokerCard; 2], board: &[PokerCard]) -> Option<Best> {
+    assert!(board.len() <= 5, "the board has at most five cards");
+
+    let cards = [&hole[..], board].concat();
+    let n = cards.len();
+    let mut best: Option<Best> = None;
+
+    for a in 0..n {
+        for b in a + 1..n {
+            for c in b + 1..n {
+                for d in c + 1..n {
+                    for e in d + 1..n {
+                        let mut hand = [cards[a], cards[b], cards[c], cards[d], cards[e]];
+                        let value = evaluate(hand);
+                        if best.is_none_or(|best| value > best.value) {
+                            hand.sort_unstable_by_key(|&card| Reverse(card));
+                            best = Some(Best { value, cards: hand });
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    best
+}
+
+/// Compares two players' hole cards against the same board.
+pub fn compare(a: [PokerCard; 2], b: [PokerCard; 2], board: &[PokerCard]) -> Ordering {
+    let value = |hole| best_hand(hole, board).map(|best| best.value);
+    value(a).cmp(&value(b))
+}
+
+/// The indices of the players who win the pot, more than one if they tie.
+pub fn showdown(players: &[[PokerCard; 2]], board: &[PokerCard]) -> Vec<usize> {
+    let values = players
+        .iter()
+        .map(|&hole| best_hand(hole, board).map(|best| best.value))
+        .collect::<Vec<_>>();
+
+    let Some(winning) = values.iter().max() else {
+        return vec![];
+    };
+
+    (0..players.len())
+        .filter(|&player| values[player] == *winning)
+        .collect()
+}
+
+/// Splits a pot between the winners of a showdown. Chips that can't be split evenly go to the
+/// winners in seat order. With no winners there is nobody to split it between.
+pub fn split_pot(pot: u64, winners: &[usize]) -> Vec<(usize, u64)> {
+    if winners.is_empty() {
+        return vec![];
+    }
+
+    let share = pot / winners.len() as u64;
+    let remainder = pot % winners.len() as u64;
+
+    winners
+        .iter()
+        .enumerate()
+        .map(|(i, &winner)| (winner, share + u64::from((i as u64) < remainder)))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::poker::{parse_cards, Type};
+
+    fn cards<const N: usize>(cards: &str) -> [PokerCard; N] {
+        parse_cards(cards).unwrap().try_into().unwrap()
+    }
+
+    #[test]
+    fn best_of_seven() {
+        let board = cards::<5>("Ah Kh 7h 2s 9c");
+
+        let best = best_hand(cards("Qh 3h"), &board).unwrap();
+        assert_eq!(best.value.hand_type, Type::Flush);
+        assert_eq!(best.cards, cards("Ah Kh Qh 7h 3h"));
+
+        let best = best_hand(cards("7c 7d"), &board).unwrap();
+        assert_eq!(best.value.hand_type, Type::ThreeOfAKind);
+        assert_eq!(best.cards, cards("Ah Kh 7h 7d 7c"));
+
+        let best = best_hand(cards("3c 4d"), &board[..3]).unwrap();
+        assert_eq!(best.value.hand_type, Type::HighCard);
+        assert_eq!(best_hand(cards("3c 4d"), &board[..2]), None);
+    }
+
+    #[test]
+    fn board_plays() {
+        let board = cards::<5>("Ts Js Qs Ks As");
+
+        assert_eq!(
+            best_hand(cards("2c 3d"), &board).unwrap().cards,
+            cards("As Ks Qs Js Ts")
+        );
+        assert_eq!(
+            showdown(&[cards("2c 3d"), cards("Ah Ac")], &board),
+            vec![0, 1]
+        );
+    }
+
+    #[test]
+    fn showdowns() {
+        let board = cards::<5>("Ah Kd 7c 2s 9h");
+        let players = [
+            cards("As Qc"),
+            cards("Ac Qd"),
+            cards("Kc Ks"),
+            cards("Ad Jh"),
+        ];
+
+        assert_eq!(compare(players[0], players[2], &board), Ordering::Less);
+        assert_eq!(compare(players[0], players[1], &board), Ordering::Equal);
+        assert_eq!(compare(players[0], players[3], &board), Ordering::Greater);
+        assert_eq!(showdown(&players, &board), vec![2]);
+        assert_eq!(showdown(&players[..2], &board), vec![0, 1]);
+    }
+
+    #[test]
+    fn bad_deals() {
+        let board = parse_cards("Ah Kd 7c 2s 9h").unwrap();
+        let hole = parse_cards("As Qc").unwrap();
+        assert_eq!(check_deal(&[&hole], &board), Ok(()));
+
+        let six = parse_cards("Ah Kd 7c 2s 9h Ts").unwrap();
+        assert_eq!(
+            check_deal(&[&hole], &six),
+            Err(DealError::TooManyBoardCards(6))
+        );
+        assert_eq!(
+            check_deal(&[&hole, &parse_cards("2c 3c 4c").unwrap()], &board),
+            Err(DealError::TooManyHoleCards {
+                player: 1,
+                count: 3
+            })
+        );
+        let duplicate = check_deal(&[&hole, &parse_cards("Kd Qd").unwrap()], &board);
+        assert_eq!(
+            duplicate,
+            Err(DealError::DuplicateCard(cards::<1>("Kd")[0]))
+        );
+        assert_eq!(
+            duplicate.unwrap_err().to_string(),
+            "Kd was dealt more than once"
+        );
+    }
+
+    #[test]
+    fn split_pots() {
+        assert_eq!(split_pot(100, &[2]), vec![(2, 100)]);
+        assert_eq!(split_pot(100, &[0, 1]), vec![(0, 50), (1, 50)]);
+        assert_eq!(split_pot(100, &[1, 2, 3]), vec![(1, 34), (2, 33), (3, 33)]);
+        assert_eq!(
+            split_pot(100, &showdown(&[], &cards::<5>("Ts Js Qs Ks As"))),
+            vec![]
+        );
+    }
+}
@@ -1,8 +1,15 @@
-use std::{collections::HashMap, ops::AddAssign};
+mod bench;
+mod classifier;
+mod equity;
+mod holdem;
+mod poker;
+mod report;
+mod rng;
+
+use std::{fmt, str::FromStr};
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
 enum Card {
-    Joker,
     Two,
     Three,
     Four,
@@ -29,15 +36,61 @@ enum Type {
     FiveOfAKind,
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone)]
 struct Hand {
-    cards: [Card; 5],
-    bid: i32,
+    cards: Vec<Card>,
+    bid: u64,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ParseErrorKind {
+    InvalidCard(char),
+    InvalidSuit(char),
+    Expected(&'static str),
+    InvalidBid(String),
 }
 
-impl From<char> for Card {
-    fn from(c: char) -> Self {
-        match c {
+/// Where and why a hand couldn't be parsed. Lines and columns start from one, and the line is
+/// only known when parsing a whole input.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ParseError {
+    line: Option<usize>,
+    column: usize,
+    kind: ParseErrorKind,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(line) = self.line {
+            write!(f, "line {line}, ")?;
+        }
+        write!(f, "column {}: ", self.column)?;
+        match &self.kind {
+            ParseErrorKind::InvalidCard(c) => write!(f, "invalid card `{c}`"),
+            ParseErrorKind::InvalidSuit(c) => write!(f, "invalid suit `{c}`"),
+            ParseErrorKind::Expected(expected) => write!(f, "expected {expected}"),
+            ParseErrorKind::InvalidBid(bid) => write!(f, "invalid bid `{bid}`"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl ParseError {
+    fn at(column: usize, kind: ParseErrorKind) -> Self {
+        Self {
+            line: None,
+            column,
+            kind,
+        }
+    }
+}
+
+impl TryFrom<char> for Card {
+    type Error = ParseError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        Ok(match c {
             'A' => Self::Ace,
             'K' => Self::King,
             'Q' => Self::Queen,
@@ -51,110 +104,482 @@ impl From<char> for Card {
             '4' => Self::Four,
             '3' => Self::Three,
             '2' => Self::Two,
-            _ => panic!("Invalid card"),
+            _ => return Err(ParseError::at(1, ParseErrorKind::InvalidCard(c))),
+        })
+    }
+}
+
+impl FromStr for Card {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        let card = chars
+            .next()
+            .ok_or(ParseError::at(1, ParseErrorKind::Expected("a card")))?
+            .try_into()?;
+
+        match chars.next() {
+            Some(_) => Err(ParseError::at(2, ParseErrorKind::Expected("a single card"))),
+            None => Ok(card),
         }
     }
 }
 
-fn hand_type(cards: [Card; 5]) -> Type {
-    let counts: HashMap<_, _> = cards.into_iter().filter(|&card| card != Card::Joker).fold(
-        HashMap::default(),
-        |mut count, card| {
-            count.entry(card).or_insert(0).add_assign(1);
-            count
-        },
-    );
+impl FromStr for Hand {
+    type Err = ParseError;
 
-    let jokers = cards
-        .into_iter()
-        .filter(|&card| card == Card::Joker)
-        .count();
+    /// Parses a line like `32T3K 765`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars().enumerate().peekable();
 
-    if jokers == 5 {
-        return Type::FiveOfAKind;
+        let mut cards = Vec::new();
+        while let Some((i, c)) = chars.next_if(|&(_, c)| c != ' ') {
+            cards.push(Card::try_from(c).map_err(|error| ParseError {
+                column: i + 1,
+                ..error
+            })?);
+        }
+        if cards.is_empty() {
+            return Err(ParseError::at(1, ParseErrorKind::Expected("a card")));
+        }
+
+        let Some((space, _)) = chars.next() else {
+            return Err(ParseError::at(
+                cards.len() + 1,
+                ParseErrorKind::Expected("a space before the bid"),
+            ));
+        };
+        let bid = &s[s.char_indices().nth(space + 1).map_or(s.len(), |(i, _)| i)..];
+        let bid = bid.parse().map_err(|_| {
+            ParseError::at(
+                space + 2,
+                match bid {
+                    "" => ParseErrorKind::Expected("a bid"),
+                    _ => ParseErrorKind::InvalidBid(bid.to_string()),
+                },
+            )
+        })?;
+
+        Ok(Hand { cards, bid })
     }
+}
 
-    match counts.len() {
-        1 => Type::FiveOfAKind,
-        2 => {
-            if counts.values().any(|&count| count + jokers == 4) {
-                Type::FourOfAKind
-            } else {
-                Type::FullHouse
-            }
+impl From<Card> for char {
+    fn from(card: Card) -> Self {
+        match card {
+            Card::Ace => 'A',
+            Card::King => 'K',
+            Card::Queen => 'Q',
+            Card::Jack => 'J',
+            Card::Ten => 'T',
+            Card::Nine => '9',
+            Card::Eight => '8',
+            Card::Seven => '7',
+            Card::Six => '6',
+            Card::Five => '5',
+            Card::Four => '4',
+            Card::Three => '3',
+            Card::Two => '2',
         }
-        3 => {
-            if counts.values().any(|&count| count + jokers == 3) {
-                Type::ThreeOfAKind
-            } else {
-                Type::TwoPair
-            }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum TieBreak {
+    /// Compare the cards one at a time in the order they were dealt.
+    InOrder,
+    /// Compare the strongest card of each hand, then the next strongest, and so on.
+    HighestFirst,
+}
+
+/// The house rules that hands are scored under.
+#[derive(Debug, Clone)]
+struct RuleSet {
+    /// Every card, from weakest to strongest.
+    ranking: Vec<Card>,
+    /// Cards that act as whatever card makes the strongest hand type.
+    wildcards: Vec<Card>,
+    tie_break: TieBreak,
+}
+
+const CARDS: [Card; 13] = [
+    Card::Two,
+    Card::Three,
+    Card::Four,
+    Card::Five,
+    Card::Six,
+    Card::Seven,
+    Card::Eight,
+    Card::Nine,
+    Card::Ten,
+    Card::Jack,
+    Card::Queen,
+    Card::King,
+    Card::Ace,
+];
+
+impl RuleSet {
+    fn standard() -> Self {
+        Self {
+            ranking: CARDS.to_vec(),
+            wildcards: vec![],
+            tie_break: TieBreak::InOrder,
         }
-        4 => {
-            if counts.values().any(|&count| count + jokers == 2) {
-                Type::OnePair
-            } else {
-                Type::HighCard
-            }
+    }
+
+    /// Jacks become jokers, which are wild but the weakest card when breaking ties.
+    fn jokers() -> Self {
+        let mut ranking = CARDS.to_vec();
+        ranking.retain(|&card| card != Card::Jack);
+        ranking.insert(0, Card::Jack);
+
+        Self {
+            ranking,
+            wildcards: vec![Card::Jack],
+            tie_break: TieBreak::InOrder,
         }
-        5 => Type::HighCard,
-        _ => unreachable!("invalid hand"),
+    }
+
+    fn strength(&self, card: Card) -> usize {
+        self.ranking
+            .iter()
+            .position(|&c| c == card)
+            .expect("card missing from ranking")
+    }
+
+    fn hand_type(&self, cards: &[Card]) -> Type {
+        hand_type(cards, &self.wildcards)
+    }
+
+    fn sort_key(&self, cards: &[Card]) -> (Type, Vec<usize>) {
+        let mut strengths = cards
+            .iter()
+            .map(|&card| self.strength(card))
+            .collect::<Vec<_>>();
+        if self.tie_break == TieBreak::HighestFirst {
+            strengths.sort_unstable_by(|a, b| b.cmp(a));
+        }
+
+        (self.hand_type(cards), strengths)
+    }
+
+    /// The hand with every wildcard replaced by the card that makes the best hand type: the most
+    /// common other card, or the strongest of those if several are equally common.
+    fn substitute(&self, cards: &[Card]) -> Vec<Card> {
+        let best = self
+            .ranking
+            .iter()
+            .rev()
+            .filter(|card| !self.wildcards.contains(card))
+            .max_by_key(|&card| {
+                (
+                    cards.iter().filter(|&c| c == card).count(),
+                    self.strength(*card),
+                )
+            });
+
+        cards
+            .iter()
+            .map(|card| match best {
+                Some(&best) if self.wildcards.contains(card) => best,
+                _ => *card,
+            })
+            .collect()
+    }
+}
+
+/// Classifies a hand of any size by how many of each card it has, with wildcards joining the
+/// largest group.
+fn hand_type(cards: &[Card], wildcards: &[Card]) -> Type {
+    let mut counts = [0; CARDS.len()];
+    let mut jokers = 0;
+    for &card in cards {
+        if wildcards.contains(&card) {
+            jokers += 1;
+        } else {
+            counts[card as usize] += 1;
+        }
+    }
+
+    counts.sort_unstable_by(|a, b| b.cmp(a));
+    counts[0] += jokers;
+
+    match (counts[0], counts[1]) {
+        (5.., _) => Type::FiveOfAKind,
+        (4, _) => Type::FourOfAKind,
+        (3, 2..) => Type::FullHouse,
+        (3, _) => Type::ThreeOfAKind,
+        (2, 2) => Type::TwoPair,
+        (2, _) => Type::OnePair,
+        _ => Type::HighCard,
     }
 }
 
-fn hands(input: &'static str) -> Vec<Hand> {
-    input
+/// Parses every hand, or reports every line that couldn't be parsed.
+fn hands(input: &str) -> Result<Vec<Hand>, Vec<ParseError>> {
+    let (hands, errors): (Vec<_>, Vec<_>) = input
         .lines()
-        .map(|line| {
-            let (cards, bid) = line.split_once(' ').unwrap();
-            Hand {
-                cards: cards
-                    .chars()
-                    .map(Card::from)
-                    .collect::<Vec<_>>()
-                    .try_into()
-                    .unwrap(),
-                bid: bid.parse().unwrap(),
-            }
+        .enumerate()
+        .map(|(i, line)| {
+            line.parse::<Hand>().map_err(|error| ParseError {
+                line: Some(i + 1),
+                ..error
+            })
         })
-        .collect()
-}
+        .partition(Result::is_ok);
 
-fn replace_jacks(mut hand: Hand) -> Hand {
-    for card in &mut hand.cards {
-        if *card == Card::Jack {
-            *card = Card::Joker;
-        }
+    if errors.is_empty() {
+        Ok(hands.into_iter().map(Result::unwrap).collect())
+    } else {
+        Err(errors.into_iter().map(Result::unwrap_err).collect())
     }
-    hand
 }
 
-fn part_1(mut hands: Vec<Hand>) -> i32 {
-    hands.sort_by(|a, b| (hand_type(a.cards), a.cards).cmp(&(hand_type(b.cards), b.cards)));
+/// Sorts hands from weakest to strongest.
+fn rank_hands(mut hands: Vec<Hand>, rules: &RuleSet) -> Vec<Hand> {
+    if hands
+        .iter()
+        .all(|hand| hand.cards.len() <= classifier::MAX_CARDS)
+    {
+        let classifier = classifier::Classifier::for_hands(rules, hands.len());
+        hands.sort_by_cached_key(|hand| classifier.sort_key(&hand.cards));
+    } else {
+        hands.sort_by_cached_key(|hand| rules.sort_key(&hand.cards));
+    }
+
     hands
+}
+
+/// The hand at `rank` won more than fits in a `u64`, on its own or added to the hands below it.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+struct WinningsOverflow {
+    rank: usize,
+}
+
+impl fmt::Display for WinningsOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "winnings overflowed at rank {}", self.rank)
+    }
+}
+
+impl std::error::Error for WinningsOverflow {}
+
+fn hand_winnings(rank: usize, bid: u64) -> Result<u64, WinningsOverflow> {
+    u64::try_from(rank)
+        .ok()
+        .and_then(|r| r.checked_mul(bid))
+        .ok_or(WinningsOverflow { rank })
+}
+
+fn winnings(hands: Vec<Hand>, rules: &RuleSet) -> Result<u64, WinningsOverflow> {
+    rank_hands(hands, rules)
         .into_iter()
         .enumerate()
-        .map(|(rank, play)| (rank + 1) as i32 * play.bid)
-        .sum()
+        .try_fold(0u64, |total, (i, play)| {
+            total
+                .checked_add(hand_winnings(i + 1, play.bid)?)
+                .ok_or(WinningsOverflow { rank: i + 1 })
+        })
+}
+
+fn part_1(hands: Vec<Hand>) -> Result<u64, WinningsOverflow> {
+    winnings(hands, &RuleSet::standard())
+}
+
+fn part_2(hands: Vec<Hand>) -> Result<u64, WinningsOverflow> {
+    winnings(hands, &RuleSet::jokers())
+}
+
+fn or_exit<T, E: fmt::Display>(result: Result<T, E>) -> T {
+    result.unwrap_or_else(|error| {
+        eprintln!("{error}");
+        std::process::exit(1);
+    })
 }
 
-fn part_2(hands: Vec<Hand>) -> i32 {
-    part_1(hands.into_iter().map(replace_jacks).collect())
+/// Reads house rules from the command line, e.g. `A23456789TJQK 2 highest-first` for aces low,
+/// twos wild and ties broken by the highest card.
+fn parse_rules(args: &[String]) -> Result<RuleSet, String> {
+    let cards = |arg: Option<&String>| {
+        arg.map_or(Ok(vec![]), |arg| {
+            arg.chars()
+                .map(|c| {
+                    Card::try_from(c).map_err(|_| format!("invalid card `{c}` in house rules"))
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })
+    };
+
+    let ranking = cards(args.first())?;
+    let mut sorted = ranking.clone();
+    sorted.sort();
+    if sorted != CARDS {
+        return Err("ranking must list every card exactly once".to_string());
+    }
+
+    Ok(RuleSet {
+        ranking,
+        wildcards: cards(args.get(1))?,
+        tie_break: match args.get(2).map(String::as_str) {
+            None | Some("in-order") => TieBreak::InOrder,
+            Some("highest-first") => TieBreak::HighestFirst,
+            Some(other) => return Err(format!("invalid tie break `{other}`")),
+        },
+    })
+}
+
+/// Parses an argument of poker cards, exiting if any of them isn't a card.
+fn poker_cards(arg: &str) -> Vec<poker::PokerCard> {
+    or_exit(poker::parse_cards(arg).map_err(|error| format!("`{arg}`: {error}")))
+}
+
+/// Plays out a showdown given as `<pot> <board> <hole cards>...`, e.g.
+/// `100 "Ah Kd 7c 2s 9h" "As Qc" "Kc Ks"`.
+fn holdem_showdown(args: &[String]) {
+    let [pot, board, players @ ..] = args else {
+        eprintln!("expected a pot size and the board");
+        std::process::exit(1);
+    };
+    let pot = or_exit(
+        pot.parse::<u64>()
+            .map_err(|_| format!("invalid pot size `{pot}`")),
+    );
+    let board = poker_cards(board);
+    let holes = players
+        .iter()
+        .map(|hole| poker_cards(hole))
+        .collect::<Vec<_>>();
+    if holes.is_empty() {
+        eprintln!("expected at least one player's hole cards");
+        std::process::exit(1);
+    }
+    or_exit(holdem::check_deal(
+        &holes.iter().map(Vec::as_slice).collect::<Vec<_>>(),
+        &board,
+    ));
+    let players = holes
+        .into_iter()
+        .map(|hole| or_exit(hole.try_into().map_err(|_| "expected two hole cards")))
+        .collect::<Vec<[poker::PokerCard; 2]>>();
+
+    let mut ranking = (0..players.len()).collect::<Vec<_>>();
+    ranking.sort_by(|&a, &b| holdem::compare(players[b], players[a], &board));
+
+    for player in ranking {
+        match holdem::best_hand(players[player], &board) {
+            Some(best) => println!(
+                "Player {}: {:?} {}",
+                player + 1,
+                best.value.hand_type,
+                best.cards.map(|card| card.to_string()).join(" ")
+            ),
+            None => println!("Player {}: not enough cards", player + 1),
+        }
+    }
+    for (player, winnings) in holdem::split_pot(pot, &holdem::showdown(&players, &board)) {
+        println!("Player {} wins {winnings}", player + 1);
+    }
+}
+
+/// Reports each player's equity given as `<board> <hole cards>...`, where any of the cards may
+/// be left out, e.g. `"Ah Kd 7c" "As Qc" "Kc"`.
+fn holdem_equity(args: &[String]) {
+    let Some((board, players)) = args.split_first() else {
+        eprintln!("expected the board");
+        std::process::exit(1);
+    };
+    let players = players
+        .iter()
+        .map(|arg| poker_cards(arg))
+        .collect::<Vec<_>>();
+    let report = or_exit(equity::equity(
+        &players,
+        &poker_cards(board),
+        &equity::Settings::default(),
+    ));
+
+    println!(
+        "{} {} deals",
+        if report.exact { "Exact," } else { "Simulated" },
+        report.deals
+    );
+    for (player, equity) in report.players.iter().enumerate() {
+        let percent = |estimate: equity::Estimate| {
+            format!(
+                "{:6.2}% ± {:.2}",
+                estimate.probability * 100.0,
+                estimate.standard_error * 100.0
+            )
+        };
+        println!(
+            "Player {}: win {}  tie {}  lose {}",
+            player + 1,
+            percent(equity.win),
+            percent(equity.tie),
+            percent(equity.lose)
+        );
+    }
 }
 
 fn main() {
-    let hands = hands(include_str!("input.txt"));
+    let hands = match hands(include_str!("input.txt")) {
+        Ok(hands) => hands,
+        Err(errors) => {
+            for error in errors {
+                eprintln!("{error}");
+            }
+            std::process::exit(1);
+        }
+    };
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
 
-    println!("Part 1: {}", part_1(hands.clone()));
-    println!("Part 2: {}", part_2(hands));
+    match args.first().map(String::as_str) {
+        Some("rules") => println!(
+            "Winnings: {}",
+            or_exit(winnings(hands, &or_exit(parse_rules(&args[1..]))))
+        ),
+        Some("report") => {
+            let rules = match args.get(1).map(String::as_str) {
+                Some("2") => RuleSet::jokers(),
+                _ => RuleSet::standard(),
+            };
+            let rows = or_exit(report::report(hands, &rules));
+            match args.get(2).map(String::as_str) {
+                Some("csv") => print!("{}", report::csv(&rows)),
+                _ => print!("{}", report::table(&rows)),
+            }
+        }
+        Some("poker") => {
+            let cards = poker_cards(&args[1..].join(" "));
+            let value =
+                poker::evaluate(or_exit(cards.try_into().map_err(|_| "expected five cards")));
+            println!("{:?} {:?}", value.hand_type, value.ranks);
+        }
+        Some("bench") => bench::run(
+            args.get(1)
+                .map_or(1_000_000, |count| count.parse().unwrap()),
+            args.get(2).map(String::as_str),
+        ),
+        Some("holdem") => holdem_showdown(&args[1..]),
+        Some("equity") => holdem_equity(&args[1..]),
+        _ => {
+            println!("Part 1: {}", or_exit(part_1(hands.clone())));
+            println!("Part 2: {}", or_exit(part_2(hands)));
+        }
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
+    pub const EXAMPLE: &str = "32T3K 765\nT55J5 684\nKK677 28\nKTJJT 220\nQQQJA 483\n";
+
     #[test]
     fn hand_types() {
+        let hand_type = |cards: [Card; 5]| hand_type(&cards, &[]);
+
         assert_eq!(
             hand_type([Card::Ace, Card::Ace, Card::Ace, Card::Ace, Card::Ace]),
             Type::FiveOfAKind
@@ -194,4 +619,203 @@ mod test {
         assert!(Type::FullHouse < Type::FourOfAKind);
         assert!(Type::FourOfAKind < Type::FiveOfAKind);
     }
+
+    #[test]
+    fn example() {
+        assert_eq!(part_1(hands(EXAMPLE).unwrap()), Ok(6440));
+        assert_eq!(part_2(hands(EXAMPLE).unwrap()), Ok(5905));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(Card::try_from('J'), Ok(Card::Jack));
+        assert_eq!("T".parse::<Card>(), Ok(Card::Ten));
+        assert_eq!(
+            "TT".parse::<Card>().unwrap_err().kind,
+            ParseErrorKind::Expected("a single card")
+        );
+        assert_eq!(
+            "32X3K 765".parse::<Hand>().unwrap_err().to_string(),
+            "column 3: invalid card `X`"
+        );
+
+        let errors = hands("32T3K 765\nT55X5 684\nKK677\nKTJJT 2x0\n QQJA 483\nQQQJA \n")
+            .unwrap_err()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            errors,
+            vec![
+                "line 2, column 4: invalid card `X`",
+                "line 3, column 6: expected a space before the bid",
+                "line 4, column 7: invalid bid `2x0`",
+                "line 5, column 1: expected a card",
+                "line 6, column 7: expected a bid",
+            ]
+        );
+    }
+
+    #[test]
+    fn other_hand_sizes() {
+        assert_eq!(
+            hand_type(&[Card::Ace, Card::Ace, Card::Ace], &[]),
+            Type::ThreeOfAKind
+        );
+        assert_eq!(
+            hand_type(&[Card::Ace, Card::Two, Card::Ace], &[]),
+            Type::OnePair
+        );
+        assert_eq!(
+            hand_type(&[Card::Ace, Card::Two, Card::Jack], &[Card::Jack]),
+            Type::OnePair
+        );
+        assert_eq!(
+            hand_type(&[Card::Ace, Card::Two, Card::Three], &[]),
+            Type::HighCard
+        );
+
+        let seven = |cards: &str| {
+            cards
+                .chars()
+                .map(|c| Card::try_from(c).unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(hand_type(&seven("AAAAAKK"), &[]), Type::FiveOfAKind);
+        assert_eq!(hand_type(&seven("AAAKKK2"), &[]), Type::FullHouse);
+        assert_eq!(hand_type(&seven("AAKKQQ2"), &[]), Type::TwoPair);
+        assert_eq!(hand_type(&seven("23456789"), &[]), Type::HighCard);
+        assert_eq!(hand_type(&seven("AAKKQQJ"), &[Card::Jack]), Type::FullHouse);
+
+        let three = hands("AA2 10\n2A2 20\n34J 30\nJJJ 40\n").unwrap();
+        assert_eq!(
+            winnings(three.clone(), &RuleSet::standard()),
+            Ok(30 + 2 * 20 + 3 * 10 + 4 * 40)
+        );
+        assert_eq!(
+            winnings(three, &RuleSet::jokers()),
+            Ok(20 + 2 * 30 + 3 * 10 + 4 * 40)
+        );
+    }
+
+    #[test]
+    fn large_tournaments() {
+        use crate::rng::Rng;
+
+        const HANDS: u64 = 1_000_000;
+        const BID: u64 = 1 << 24;
+
+        // With equal bids the total doesn't depend on the order, and is far beyond an `i32`.
+        let input = bench::generate(&mut Rng::new(39), HANDS as usize, 1)
+            .lines()
+            .map(|line| format!("{} {BID}\n", &line[..5]))
+            .collect::<String>();
+        let equal = hands(&input).unwrap();
+        assert_eq!(
+            winnings(equal, &RuleSet::jokers()),
+            Ok(BID * HANDS * (HANDS + 1) / 2)
+        );
+
+        let varied = hands(&bench::generate(&mut Rng::new(39), HANDS as usize, BID)).unwrap();
+        let expected = rank_hands(varied.clone(), &RuleSet::jokers())
+            .iter()
+            .enumerate()
+            .map(|(i, hand)| (i as u128 + 1) * u128::from(hand.bid))
+            .sum::<u128>();
+        assert!(expected > i32::MAX as u128);
+        assert_eq!(
+            winnings(varied, &RuleSet::jokers()).map(u128::from),
+            Ok(expected)
+        );
+    }
+
+    #[test]
+    fn overflow() {
+        let input = format!("AAAAA {}\nKKKKK {}\n", u64::MAX / 2, u64::MAX / 2);
+        assert_eq!(
+            winnings(hands(&input).unwrap(), &RuleSet::standard()),
+            Err(WinningsOverflow { rank: 2 })
+        );
+
+        let input = format!("AAAAA {}\nKKKKK 1\n", u64::MAX / 2 + 1);
+        assert_eq!(
+            winnings(hands(&input).unwrap(), &RuleSet::standard()),
+            Err(WinningsOverflow { rank: 2 })
+        );
+        assert_eq!(
+            hands("AAAAA -5\n").unwrap_err()[0].kind,
+            ParseErrorKind::InvalidBid("-5".to_string())
+        );
+    }
+
+    #[test]
+    fn house_rules() {
+        let twos_wild_aces_low = RuleSet {
+            ranking: [&[Card::Ace][..], &CARDS[..12]].concat(),
+            wildcards: vec![Card::Two],
+            tie_break: TieBreak::InOrder,
+        };
+
+        assert_eq!(
+            twos_wild_aces_low.hand_type(&[Card::Two, Card::Ace, Card::Ace, Card::Two, Card::Five]),
+            Type::FourOfAKind
+        );
+        assert!(
+            twos_wild_aces_low.sort_key(&[Card::Ace, Card::Ace, Card::King, Card::King, Card::Two])
+                < twos_wild_aces_low.sort_key(&[
+                    Card::King,
+                    Card::King,
+                    Card::Ace,
+                    Card::Ace,
+                    Card::Two
+                ])
+        );
+
+        let highest_first = RuleSet {
+            tie_break: TieBreak::HighestFirst,
+            ..RuleSet::standard()
+        };
+        assert!(
+            highest_first.sort_key(&[Card::Two, Card::Ace, Card::Five, Card::Four, Card::Three])
+                > highest_first.sort_key(&[
+                    Card::King,
+                    Card::Queen,
+                    Card::Jack,
+                    Card::Nine,
+                    Card::Eight
+                ])
+        );
+
+        // The same wildcard rules as part 2, but with jacks keeping their usual strength.
+        let strong_jokers = RuleSet {
+            wildcards: vec![Card::Jack],
+            ..RuleSet::standard()
+        };
+        assert_eq!(winnings(hands(EXAMPLE).unwrap(), &strong_jokers), Ok(5905));
+    }
+
+    #[test]
+    fn house_rules_from_arguments() {
+        let rules = |args: &[&str]| {
+            parse_rules(&args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>())
+        };
+
+        let aces_low = rules(&["A23456789TJQK", "2", "highest-first"]).unwrap();
+        assert_eq!(aces_low.strength(Card::Ace), 0);
+        assert_eq!(aces_low.wildcards, [Card::Two]);
+        assert_eq!(aces_low.tie_break, TieBreak::HighestFirst);
+
+        assert_eq!(
+            rules(&["A23456789TJQ"]).unwrap_err(),
+            "ranking must list every card exactly once"
+        );
+        assert_eq!(
+            rules(&["A23456789TJQK", "X"]).unwrap_err(),
+            "invalid card `X` in house rules"
+        );
+        assert_eq!(
+            rules(&["A23456789TJQK", "", "sideways"]).unwrap_err(),
+            "invalid tie break `sideways`"
+        );
+    }
 }
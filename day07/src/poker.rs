@@ -0,0 +1,262 @@
+//! Standard five-card poker, where suits matter and straights and flushes count.
+
+use std::{cmp::Reverse, fmt, str::FromStr};
+
+use crate::{Card, ParseError, ParseErrorKind, CARDS};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum Suit {
+    Clubs,
+    Diamonds,
+    Hearts,
+    Spades,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct PokerCard {
+    pub rank: Card,
+    pub suit: Suit,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum Type {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+}
+
+/// How strong a hand is. Hands of the same type are compared by `ranks`, which lists the most
+/// significant card first: the larger groups, then the kickers.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Value {
+    pub hand_type: Type,
+    pub ranks: [Card; 5],
+}
+
+impl TryFrom<char> for Suit {
+    type Error = ParseError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        Ok(match c {
+            'c' | 'C' => Self::Clubs,
+            'd' | 'D' => Self::Diamonds,
+            'h' | 'H' => Self::Hearts,
+            's' | 'S' => Self::Spades,
+            _ => return Err(ParseError::at(1, ParseErrorKind::InvalidSuit(c))),
+        })
+    }
+}
+
+impl From<Suit> for char {
+    fn from(suit: Suit) -> Self {
+        match suit {
+            Suit::Clubs => 'c',
+            Suit::Diamonds => 'd',
+            Suit::Hearts => 'h',
+            Suit::Spades => 's',
+        }
+    }
+}
+
+impl FromStr for PokerCard {
+    type Err = ParseError;
+
+    /// Parses a rank followed by a suit, like `Ah` or `Tc`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        let rank = chars
+            .next()
+            .ok_or(ParseError::at(1, ParseErrorKind::Expected("a card")))?
+            .try_into()?;
+        let suit = Suit::try_from(
+            chars
+                .next()
+                .ok_or(ParseError::at(2, ParseErrorKind::Expected("a suit")))?,
+        )
+        .map_err(|error| ParseError { column: 2, ..error })?;
+
+        match chars.next() {
+            Some(_) => Err(ParseError::at(3, ParseErrorKind::Expected("a single card"))),
+            None => Ok(PokerCard { rank, suit }),
+        }
+    }
+}
+
+impl fmt::Display for PokerCard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", char::from(self.rank), char::from(self.suit))
+    }
+}
+
+/// Parses cards separated by whitespace, like `Ah Kd 7c`. The column of an error counts from the
+/// start of the whole text.
+pub fn parse_cards(s: &str) -> Result<Vec<PokerCard>, ParseError> {
+    let mut cards = Vec::new();
+    let mut end = 0;
+    for card in s.split_whitespace() {
+        let start = end + s[end..].find(card).unwrap();
+        end = start + card.len();
+
+        let column = s[..start].chars().count();
+        cards.push(card.parse().map_err(|error: ParseError| ParseError {
+            column: column + error.column,
+            ..error
+        })?);
+    }
+    Ok(cards)
+}
+
+/// Every card in a standard 52 card deck.
+pub fn deck() -> impl Iterator<Item = PokerCard> {
+    const SUITS: [Suit; 4] = [Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades];
+
+    CARDS
+        .into_iter()
+        .flat_map(|rank| SUITS.into_iter().map(move |suit| PokerCard { rank, suit }))
+}
+
+pub fn evaluate(cards: [PokerCard; 5]) -> Value {
+    let mut counts = [0usize; CARDS.len()];
+    for card in cards {
+        counts[card.rank as usize] += 1;
+    }
+    let count = |rank: Card| counts[rank as usize];
+
+    let mut ranks = cards.map(|card| card.rank);
+    ranks.sort_unstable_by_key(|&rank| Reverse((count(rank), rank)));
+
+    let largest = count(ranks[0]);
+    let second = ranks.get(largest).map_or(0, |&rank| count(rank));
+
+    let flush = cards.iter().all(|card| card.suit == cards[0].suit);
+    let wheel = ranks == [Card::Ace, Card::Five, Card::Four, Card::Three, Card::Two];
+    let straight = largest == 1 && (ranks[0] as usize - ranks[4] as usize == 4 || wheel);
+    if wheel {
+        // The ace plays low, so a five-high straight loses to every other straight.
+        ranks.rotate_left(1);
+    }
+
+    let hand_type = match (largest, second) {
+        _ if straight && flush => Type::StraightFlush,
+        (4, _) => Type::FourOfAKind,
+        (3, 2) => Type::FullHouse,
+        _ if flush => Type::Flush,
+        _ if straight => Type::Straight,
+        (3, _) => Type::ThreeOfAKind,
+        (2, 2) => Type::TwoPair,
+        (2, _) => Type::OnePair,
+        _ => Type::HighCard,
+    };
+
+    Value { hand_type, ranks }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn hand(cards: &str) -> [PokerCard; 5] {
+        parse_cards(cards).unwrap().try_into().unwrap()
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            "Ah".parse(),
+            Ok(PokerCard {
+                rank: Card::Ace,
+                suit: Suit::Hearts
+            })
+        );
+
+        let error = |card: &str| card.parse::<PokerCard>().unwrap_err().to_string();
+        assert_eq!(error(""), "column 1: expected a card");
+        assert_eq!(error("Xh"), "column 1: invalid card `X`");
+        assert_eq!(error("A"), "column 2: expected a suit");
+        assert_eq!(error("Ax"), "column 2: invalid suit `x`");
+        assert_eq!(error("Ahh"), "column 3: expected a single card");
+
+        assert_eq!(parse_cards(" Ah\tKd ").unwrap().len(), 2);
+        assert_eq!(parse_cards("").unwrap(), []);
+        assert_eq!(
+            parse_cards("Ah Kd  7x").unwrap_err().to_string(),
+            "column 9: invalid suit `x`"
+        );
+    }
+
+    #[test]
+    fn hand_types() {
+        assert_eq!(
+            evaluate(hand("Ts Js Qs Ks As")).hand_type,
+            Type::StraightFlush
+        );
+        assert_eq!(
+            evaluate(hand("5d 4d 3d 2d Ad")).hand_type,
+            Type::StraightFlush
+        );
+        assert_eq!(
+            evaluate(hand("9c 9d 9h 9s 2c")).hand_type,
+            Type::FourOfAKind
+        );
+        assert_eq!(evaluate(hand("9c 9d 9h 2s 2c")).hand_type, Type::FullHouse);
+        assert_eq!(evaluate(hand("2h 7h 9h Jh Kh")).hand_type, Type::Flush);
+        assert_eq!(evaluate(hand("5d 4c 3d 2d Ad")).hand_type, Type::Straight);
+        assert_eq!(
+            evaluate(hand("9c 9d 9h 3s 2c")).hand_type,
+            Type::ThreeOfAKind
+        );
+        assert_eq!(evaluate(hand("9c 9d 3h 3s 2c")).hand_type, Type::TwoPair);
+        assert_eq!(evaluate(hand("9c 9d 4h 3s 2c")).hand_type, Type::OnePair);
+        assert_eq!(evaluate(hand("Qc 4d 5h 3s 2c")).hand_type, Type::HighCard);
+        assert_eq!(evaluate(hand("Qc Kd Ah 3s 2c")).hand_type, Type::HighCard);
+    }
+
+    #[test]
+    fn kickers() {
+        assert!(evaluate(hand("5d 4c 3d 2d Ad")) < evaluate(hand("6d 5c 4d 3d 2d")));
+        assert!(evaluate(hand("9c 9d 3h 3s 2c")) < evaluate(hand("9h 9s 3c 3d 4c")));
+        assert!(evaluate(hand("9c 9d Ah Ks 2c")) < evaluate(hand("Tc Td 4h 3s 2c")));
+        assert!(evaluate(hand("2c 2d 2h As Ac")) < evaluate(hand("3c 3d 3h 4s 4c")));
+        assert!(evaluate(hand("Ac Kd Qh Js 9c")) > evaluate(hand("Ad Kc Qs Jh 8d")));
+        assert_eq!(
+            evaluate(hand("Ac Kd Qh Js 9c")),
+            evaluate(hand("Ad Kc Qs Jh 9h"))
+        );
+    }
+
+    #[test]
+    fn all_five_card_hands() {
+        let deck = deck().collect::<Vec<_>>();
+        let mut counts = std::collections::HashMap::<Type, usize>::default();
+
+        for a in 0..deck.len() {
+            for b in a + 1..deck.len() {
+                for c in b + 1..deck.len() {
+                    for d in c + 1..deck.len() {
+                        for e in d + 1..deck.len() {
+                            let value = evaluate([deck[a], deck[b], deck[c], deck[d], deck[e]]);
+                            *counts.entry(value.hand_type).or_default() += 1;
+                        }
+                    }
+                }
+            }
+        }
+
+        assert_eq!(counts[&Type::StraightFlush], 40);
+        assert_eq!(counts[&Type::FourOfAKind], 624);
+        assert_eq!(counts[&Type::FullHouse], 3_744);
+        assert_eq!(counts[&Type::Flush], 5_108);
+        assert_eq!(counts[&Type::Straight], 10_200);
+        assert_eq!(counts[&Type::ThreeOfAKind], 54_912);
+        assert_eq!(counts[&Type::TwoPair], 123_552);
+        assert_eq!(counts[&Type::OnePair], 1_098_240);
+        assert_eq!(counts[&Type::HighCard], 1_302_540);
+        assert_eq!(counts.values().sum::<usize>(), 2_598_960);
+    }
+}
@@ -0,0 +1,147 @@
+//! A breakdown of how every hand was ranked and what it won, so that results can be audited.
+
+use crate::{hand_winnings, rank_hands, Card, Hand, RuleSet, Type, WinningsOverflow};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Row {
+    pub rank: usize,
+    pub cards: Vec<Card>,
+    pub hand_type: Type,
+    /// The hand with its wildcards replaced by the cards they stood in for.
+    pub best: Vec<Card>,
+    pub bid: u64,
+    pub winnings: u64,
+}
+
+pub fn report(hands: Vec<Hand>, rules: &RuleSet) -> Result<Vec<Row>, WinningsOverflow> {
+    rank_hands(hands, rules)
+        .into_iter()
+        .enumerate()
+        .map(|(i, hand)| {
+            Ok(Row {
+                rank: i + 1,
+                hand_type: rules.hand_type(&hand.cards),
+                best: rules.substitute(&hand.cards),
+                winnings: hand_winnings(i + 1, hand.bid)?,
+                cards: hand.cards,
+                bid: hand.bid,
+            })
+        })
+        .collect()
+}
+
+fn cards(cards: &[Card]) -> String {
+    cards.iter().map(|&card| char::from(card)).collect()
+}
+
+/// The wildcards in a row and what they became, e.g. `J→K`.
+fn substitutions(row: &Row) -> String {
+    let mut substitutions = row
+        .cards
+        .iter()
+        .zip(&row.best)
+        .filter(|(card, best)| card != best)
+        .map(|(&card, &best)| format!("{}→{}", char::from(card), char::from(best)))
+        .collect::<Vec<_>>();
+    substitutions.dedup();
+    substitutions.join(" ")
+}
+
+fn fields(row: &Row) -> [String; 7] {
+    [
+        row.rank.to_string(),
+        cards(&row.cards),
+        format!("{:?}", row.hand_type),
+        cards(&row.best),
+        substitutions(row),
+        row.bid.to_string(),
+        row.winnings.to_string(),
+    ]
+}
+
+const HEADERS: [&str; 7] = ["Rank", "Hand", "Type", "Best", "Wild", "Bid", "Winnings"];
+
+pub fn table(rows: &[Row]) -> String {
+    let rows = rows.iter().map(fields).collect::<Vec<_>>();
+
+    let mut widths = HEADERS.map(|header| header.chars().count());
+    for row in &rows {
+        for (width, field) in widths.iter_mut().zip(row) {
+            *width = (*width).max(field.chars().count());
+        }
+    }
+
+    let line = |fields: [&str; 7]| {
+        let columns = fields
+            .iter()
+            .zip(widths)
+            .enumerate()
+            .map(|(i, (field, width))| {
+                let padding = " ".repeat(width - field.chars().count());
+                // Numbers are right aligned and everything else left aligned.
+                match i {
+                    0 | 5 | 6 => format!("{padding}{field}"),
+                    _ => format!("{field}{padding}"),
+                }
+            })
+            .collect::<Vec<_>>();
+        format!("{}\n", columns.join("  ").trim_end())
+    };
+
+    let mut table = line(HEADERS);
+    for row in &rows {
+        table += &line(row.each_ref().map(String::as_str));
+    }
+    table
+}
+
+pub fn csv(rows: &[Row]) -> String {
+    let mut csv = format!("{}\n", HEADERS.join(",").to_lowercase());
+    for row in rows {
+        csv += &format!("{}\n", fields(row).join(","));
+    }
+    csv
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{hands, test::EXAMPLE};
+
+    #[test]
+    fn jokers() {
+        let rows = report(hands(EXAMPLE).unwrap(), &RuleSet::jokers()).unwrap();
+
+        assert_eq!(rows.iter().map(|row| row.winnings).sum::<u64>(), 5905);
+        assert_eq!(cards(&rows[4].cards), "KTJJT");
+        assert_eq!(cards(&rows[4].best), "KTTTT");
+        assert_eq!(rows[4].hand_type, Type::FourOfAKind);
+        assert_eq!(substitutions(&rows[4]), "J→T");
+
+        let rules = RuleSet::standard();
+        for row in &rows {
+            assert_eq!(rules.hand_type(&row.best), row.hand_type);
+        }
+    }
+
+    #[test]
+    fn rendering() {
+        let rows = report(hands(EXAMPLE).unwrap(), &RuleSet::jokers()).unwrap();
+
+        assert_eq!(
+            table(&rows),
+            "\
+Rank  Hand   Type         Best   Wild  Bid  Winnings
+   1  32T3K  OnePair      32T3K        765       765
+   2  KK677  TwoPair      KK677         28        56
+   3  T55J5  FourOfAKind  T5555  J→5   684      2052
+   4  QQQJA  FourOfAKind  QQQQA  J→Q   483      1932
+   5  KTJJT  FourOfAKind  KTTTT  J→T   220      1100
+"
+        );
+        assert_eq!(
+            csv(&rows[..2]),
+            "rank,hand,type,best,wild,bid,winnings\n1,32T3K,OnePair,32T3K,,765,765\n2,KK677,TwoPair,KK677,,28,56\n"
+        );
+    }
+}
@@ -0,0 +1,93 @@
+//! Compares walking the network by name against walking the compiled network, one step and one
+//! block of instructions at a time, on generated networks far larger than the puzzle input.
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+use crate::network::Network;
+use crate::rng::Rng;
+use crate::{or_exit, parse, Direction, Node};
+
+/// The number of instructions in generated networks, about the same as the puzzle input.
+const INSTRUCTIONS: usize = 283;
+
+/// Generates a network in the puzzle input format with random instructions and edges.
+fn generate(rng: &mut Rng, nodes: usize) -> &'static str {
+    let mut input = (0..INSTRUCTIONS)
+        .map(|_| if rng.below(2) == 0 { 'L' } else { 'R' })
+        .collect::<String>();
+    input.push_str("\n\n");
+
+    let name = |node: u64| format!("{node:06}");
+    for node in 0..nodes as u64 {
+        let (left, right) = (rng.below(nodes as u64), rng.below(nodes as u64));
+        input.push_str(&format!(
+            "{} = ({}, {})\n",
+            name(node),
+            name(left),
+            name(right)
+        ));
+    }
+
+    Box::leak(input.into_boxed_str())
+}
+
+fn hash_map_walk(
+    instructions: &[Direction],
+    nodes: &HashMap<&'static str, Node>,
+    start: &'static str,
+    steps: u64,
+) -> &'static str {
+    let mut instructions = instructions.iter().cycle();
+    let mut node = start;
+    for _ in 0..steps {
+        let next = nodes.get(node).unwrap();
+        node = match instructions.next().unwrap() {
+            Direction::Left => next.left,
+            Direction::Right => next.right,
+        };
+    }
+    node
+}
+
+fn time<T>(name: &str, steps: u64, f: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let result = f();
+    let elapsed = start.elapsed();
+    if steps == 0 {
+        println!("  {name:<10} {:>20} {elapsed:>10.1?}", "");
+    } else {
+        println!(
+            "  {name:<10} {steps:>14} steps {elapsed:>10.1?} {:>10.1} M steps/s",
+            steps as f64 / elapsed.as_secs_f64() / 1e6
+        );
+    }
+    result
+}
+
+pub fn run(nodes: usize, steps: u64) {
+    let input = generate(&mut Rng::new(0x2023_1208), nodes);
+    let (instructions, hash_map) = or_exit(parse(input));
+    println!("{nodes} nodes, {INSTRUCTIONS} instructions");
+
+    let network = time("compile", 0, || {
+        or_exit(Network::compile(&instructions, &hash_map))
+    });
+    let start = network.name(0);
+
+    let by_name = time("hash map", steps, || {
+        hash_map_walk(&instructions, &hash_map, start, steps)
+    });
+    let stepped = time("compiled", steps, || {
+        (0..steps).fold(0, |node, step| {
+            network.next(node, (step % INSTRUCTIONS as u64) as usize)
+        })
+    });
+    let jumped = time("blocks", steps, || network.walk(0, steps));
+    assert_eq!(by_name, network.name(stepped));
+    assert_eq!(stepped, jumped);
+
+    let steps = steps * 1_000;
+    let node = time("blocks", steps, || network.walk(0, steps));
+    println!("  after {steps} steps: {}", network.name(node));
+}
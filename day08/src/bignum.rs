@@ -0,0 +1,220 @@
+//! Natural numbers that stay in a `u64` while they fit, and fall back to a big integer when the
+//! ghosts' combined period grows beyond that.
+
+use std::{cmp::Ordering, fmt};
+
+/// An arbitrarily large natural number, stored as base 2^32 digits with the least significant
+/// first and no leading zeros.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct BigUint {
+    digits: Vec<u32>,
+}
+
+impl BigUint {
+    fn normalized(mut digits: Vec<u32>) -> Self {
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+        Self { digits }
+    }
+
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.digits[..] {
+            [] => Some(0),
+            [low] => Some(u64::from(low)),
+            [low, high] => Some(u64::from(high) << 32 | u64::from(low)),
+            _ => None,
+        }
+    }
+
+    pub fn add(&self, other: &BigUint) -> BigUint {
+        let len = self.digits.len().max(other.digits.len());
+        let mut digits = Vec::with_capacity(len + 1);
+        let mut carry = 0;
+        for i in 0..len {
+            let sum = u64::from(self.digits.get(i).copied().unwrap_or(0))
+                + u64::from(other.digits.get(i).copied().unwrap_or(0))
+                + carry;
+            digits.push(sum as u32);
+            carry = sum >> 32;
+        }
+        digits.push(carry as u32);
+        Self::normalized(digits)
+    }
+
+    pub fn mul(&self, other: &BigUint) -> BigUint {
+        let mut digits = vec![0u32; self.digits.len() + other.digits.len()];
+        for (i, &a) in self.digits.iter().enumerate() {
+            let mut carry = 0;
+            for (j, &b) in other.digits.iter().enumerate() {
+                let product = u64::from(a) * u64::from(b) + u64::from(digits[i + j]) + carry;
+                digits[i + j] = product as u32;
+                carry = product >> 32;
+            }
+            digits[i + other.digits.len()] = carry as u32;
+        }
+        Self::normalized(digits)
+    }
+
+    /// Divides by a small divisor, returning the quotient and remainder.
+    pub fn div_rem(&self, divisor: u64) -> (BigUint, u64) {
+        assert_ne!(divisor, 0, "division by zero");
+
+        let mut quotient = vec![0u32; self.digits.len()];
+        let mut remainder = 0u128;
+        for (i, &digit) in self.digits.iter().enumerate().rev() {
+            let value = remainder << 32 | u128::from(digit);
+            quotient[i] = (value / u128::from(divisor)) as u32;
+            remainder = value % u128::from(divisor);
+        }
+        (Self::normalized(quotient), remainder as u64)
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(value: u64) -> Self {
+        Self::normalized(vec![value as u32, (value >> 32) as u32])
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.digits
+            .len()
+            .cmp(&other.digits.len())
+            .then_with(|| self.digits.iter().rev().cmp(other.digits.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const CHUNK: u64 = 1_000_000_000;
+
+        // Peel off nine decimal digits at a time, least significant first.
+        let mut chunks = Vec::new();
+        let mut rest = self.clone();
+        loop {
+            let (quotient, chunk) = rest.div_rem(CHUNK);
+            chunks.push(chunk);
+            if quotient.digits.is_empty() {
+                break;
+            }
+            rest = quotient;
+        }
+
+        write!(f, "{}", chunks.pop().unwrap())?;
+        for chunk in chunks.iter().rev() {
+            write!(f, "{chunk:09}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A natural number that uses checked `u64` arithmetic until it overflows. A `Big` number is
+/// always larger than `u64::MAX`, so numbers compare correctly by variant first.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Number {
+    Small(u64),
+    Big(BigUint),
+}
+
+impl Number {
+    fn from_big(value: BigUint) -> Self {
+        value.to_u64().map_or(Number::Big(value), Number::Small)
+    }
+
+    fn to_big(&self) -> BigUint {
+        match self {
+            Number::Small(value) => BigUint::from(*value),
+            Number::Big(value) => value.clone(),
+        }
+    }
+
+    pub fn add(&self, other: &Number) -> Number {
+        match (self, other) {
+            (&Number::Small(a), &Number::Small(b)) => a.checked_add(b).map(Number::Small),
+            _ => None,
+        }
+        .unwrap_or_else(|| Self::from_big(self.to_big().add(&other.to_big())))
+    }
+
+    pub fn mul(&self, other: &Number) -> Number {
+        match (self, other) {
+            (&Number::Small(a), &Number::Small(b)) => a.checked_mul(b).map(Number::Small),
+            _ => None,
+        }
+        .unwrap_or_else(|| Self::from_big(self.to_big().mul(&other.to_big())))
+    }
+
+    pub fn rem(&self, divisor: u64) -> u64 {
+        match self {
+            Number::Small(value) => value % divisor,
+            Number::Big(value) => value.div_rem(divisor).1,
+        }
+    }
+}
+
+impl From<u64> for Number {
+    fn from(value: u64) -> Self {
+        Number::Small(value)
+    }
+}
+
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Number::Small(value) => write!(f, "{value}"),
+            Number::Big(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn big_arithmetic() {
+        let max = BigUint::from(u64::MAX);
+        let two_to_the_64 = max.add(&BigUint::from(1));
+
+        assert_eq!(max.to_u64(), Some(u64::MAX));
+        assert_eq!(two_to_the_64.to_u64(), None);
+        assert_eq!(two_to_the_64.to_string(), "18446744073709551616");
+        assert_eq!(
+            two_to_the_64.mul(&two_to_the_64).to_string(),
+            "340282366920938463463374607431768211456"
+        );
+        assert_eq!(max.mul(&max).div_rem(u64::MAX), (max.clone(), 0));
+        assert_eq!(two_to_the_64.div_rem(10).1, 6);
+        assert_eq!(BigUint::from(0).to_string(), "0");
+        assert_eq!(BigUint::from(1_000_000_000).to_string(), "1000000000");
+        assert!(max < two_to_the_64);
+        assert!(BigUint::from(7) < BigUint::from(8));
+    }
+
+    #[test]
+    fn falls_back_when_overflowing() {
+        let max = Number::from(u64::MAX);
+        assert_eq!(Number::from(2).mul(&Number::from(3)), Number::Small(6));
+
+        let sum = max.add(&Number::from(1));
+        assert!(matches!(sum, Number::Big(_)));
+        assert_eq!(sum.to_string(), "18446744073709551616");
+        assert_eq!(sum.rem(1 << 32), 0);
+        assert!(max < sum);
+
+        let product = max.mul(&max);
+        assert_eq!(
+            product.to_string(),
+            "340282366920938463426481119284349108225"
+        );
+        assert_eq!(product.rem(u64::MAX), 0);
+    }
+}
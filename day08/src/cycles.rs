@@ -0,0 +1,305 @@
+//! Where each ghost's walk starts repeating, and the first step at which every ghost is on a goal
+//! at the same time.
+
+use crate::bignum::Number;
+use crate::network::Network;
+
+/// The goal visits of a single ghost. A ghost's state is its node together with its position in
+/// the instructions, so after a tail of distinct states it must enter a cycle and repeat forever.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Cycle {
+    /// The number of steps taken before the cycle is entered.
+    pub tail: u64,
+    pub length: u64,
+    /// Every step before `tail + length` at which the ghost is on a goal, in order.
+    pub hits: Vec<u64>,
+}
+
+/// The number of steps walked from `start` before the walk enters its cycle, and the length of
+/// the cycle.
+///
+/// Only the states at block boundaries (on the first instruction) are recorded, one per node.
+/// The cycle must be a whole number of blocks long, so it is the cycle of the block boundaries,
+/// and the tail ends somewhere in the block before the first repeated boundary.
+pub fn tail_and_length(start: usize, network: &Network) -> (u64, u64) {
+    let instructions = network.instructions() as u64;
+    // The block at whose start each node was first seen.
+    let mut seen = vec![None; network.len()];
+    let mut boundaries = Vec::new();
+    let mut node = start;
+
+    let first = loop {
+        if let Some(first) = seen[node] {
+            break first;
+        }
+        seen[node] = Some(boundaries.len());
+        boundaries.push(node);
+        node = network.walk(node, instructions);
+    };
+    let length = (boundaries.len() - first) as u64 * instructions;
+
+    // The walks from the boundaries before the first repeated one and before its repeat differ at
+    // first, and meet where the cycle is entered.
+    let Some(previous) = first.checked_sub(1) else {
+        return (0, length);
+    };
+    let (mut a, mut b) = (boundaries[previous], boundaries[boundaries.len() - 1]);
+    let mut cursor = 0;
+    while a != b {
+        a = network.next(a, cursor);
+        b = network.next(b, cursor);
+        cursor += 1;
+    }
+    (previous as u64 * instructions + cursor as u64, length)
+}
+
+impl Cycle {
+    pub fn analyse(start: usize, network: &Network, goals: &[bool]) -> Self {
+        let (tail, length) = tail_and_length(start, network);
+        let mut hits = Vec::new();
+        let mut node = start;
+
+        for step in 0..tail + length {
+            if goals[node] {
+                hits.push(step);
+            }
+            node = network.next(node, (step % network.instructions() as u64) as usize);
+        }
+
+        Self { tail, length, hits }
+    }
+
+    /// Whether the ghost is on a goal after `step` steps.
+    pub fn at_goal(&self, step: u64) -> bool {
+        let step = if step < self.tail {
+            step
+        } else {
+            self.tail + (step - self.tail) % self.length
+        };
+        self.hits.binary_search(&step).is_ok()
+    }
+
+    fn cycle_hits(&self) -> &[u64] {
+        &self.hits[self.hits.partition_point(|&hit| hit < self.tail)..]
+    }
+}
+
+/// Finds the first step (after at least one) at which every ghost is on a goal at once, or `None`
+/// if that never happens.
+pub fn first_common_hit(cycles: &[Cycle]) -> Option<Number> {
+    let latest = cycles.iter().max_by_key(|cycle| cycle.tail)?;
+
+    // Until the last ghost enters its cycle, it can only be on a goal at one of its tail hits.
+    let early = latest.hits[..latest.hits.len() - latest.cycle_hits().len()]
+        .iter()
+        .copied()
+        .filter(|&step| step >= 1)
+        .find(|&step| cycles.iter().all(|cycle| cycle.at_goal(step)));
+    if let Some(step) = early {
+        return Some(Number::from(step));
+    }
+
+    // From then on, each ghost is on a goal at the steps congruent to one of its cycle hits.
+    let mut congruences = vec![(Number::from(0), Number::from(1))];
+    for cycle in cycles {
+        congruences = congruences
+            .iter()
+            .flat_map(|congruence| {
+                cycle
+                    .cycle_hits()
+                    .iter()
+                    .filter_map(move |&hit| combine(congruence, hit % cycle.length, cycle.length))
+            })
+            .collect();
+        congruences.sort_unstable();
+        congruences.dedup();
+    }
+
+    let from = latest.tail.max(1);
+    congruences
+        .into_iter()
+        .map(|(r, m)| match (&r, &m) {
+            (Number::Small(small), _) if *small < from => {
+                // The first step from `from` onwards that is congruent to `r`.
+                let periods = match m {
+                    Number::Small(m) => (from - small).div_ceil(m),
+                    Number::Big(_) => 1,
+                };
+                r.add(&m.mul(&Number::from(periods)))
+            }
+            _ => r,
+        })
+        .min()
+}
+
+/// Returns `(g, x, y)` such that `a * x + b * y = g`, where `g` is the greatest common divisor.
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a, 1, 0)
+    } else {
+        let (g, x, y) = extended_gcd(b, a % b);
+        (g, y, x - a / b * y)
+    }
+}
+
+/// Solves `x ≡ a (mod m)` and `x ≡ b (mod n)` together as `x ≡ r (mod lcm(m, n))`, where the
+/// moduli needn't be coprime. Returns `None` if there is no solution.
+///
+/// `m` is the period of every ghost combined so far and may be huge, but `n` is a single ghost's
+/// period, so everything except `r` and the new period can be worked out modulo `n`.
+fn combine((a, m): &(Number, Number), b: u64, n: u64) -> Option<(Number, Number)> {
+    let (g, p, _) = extended_gcd(i128::from(m.rem(n)), i128::from(n));
+    let diff = (i128::from(b) - i128::from(a.rem(n))).rem_euclid(i128::from(n));
+    if diff % g != 0 {
+        return None;
+    }
+
+    // x = a + m * k, where m * k ≡ diff (mod n), so k ≡ (diff / g) * (m / g)⁻¹ (mod n / g).
+    let modulus = n / g as u64;
+    let k = ((diff / g) as u128 * p.rem_euclid(i128::from(modulus)) as u128) % u128::from(modulus);
+
+    let x = a.add(&m.mul(&Number::from(k as u64)));
+    Some((x, m.mul(&Number::from(modulus))))
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::load;
+    use crate::rng::Rng;
+
+    #[test]
+    fn example_cycles() {
+        let network = load(include_str!("../ghosts.txt")).unwrap();
+        let goals = network.select(|node| node.ends_with('Z'));
+        let start = |name| network.index(name).unwrap();
+
+        assert_eq!(
+            Cycle::analyse(start("11A"), &network, &goals),
+            Cycle {
+                tail: 1,
+                length: 2,
+                hits: vec![2]
+            }
+        );
+        assert_eq!(
+            Cycle::analyse(start("22A"), &network, &goals),
+            Cycle {
+                tail: 1,
+                length: 6,
+                hits: vec![3, 6]
+            }
+        );
+    }
+
+    #[test]
+    fn tails_between_block_boundaries() {
+        // Compare against recording every state, on small random networks whose walks often enter
+        // their cycle partway through a block.
+        let mut rng = Rng::new(8);
+        for _ in 0..200 {
+            let nodes = 1 + rng.below(6);
+            let mut input = (0..1 + rng.below(4))
+                .map(|_| if rng.below(2) == 0 { 'L' } else { 'R' })
+                .collect::<String>();
+            input.push_str("\n\n");
+            for node in 0..nodes {
+                let (left, right) = (rng.below(nodes), rng.below(nodes));
+                input.push_str(&format!("N{node} = (N{left}, N{right})\n"));
+            }
+            let network = load(Box::leak(input.into_boxed_str())).unwrap();
+
+            let mut seen = HashMap::new();
+            let mut node = 0;
+            for step in 0.. {
+                let cursor = (step % network.instructions() as u64) as usize;
+                if let Some(&first) = seen.get(&(node, cursor)) {
+                    assert_eq!(tail_and_length(0, &network), (first, step - first));
+                    break;
+                }
+                seen.insert((node, cursor), step);
+                node = network.next(node, cursor);
+            }
+        }
+    }
+
+    #[test]
+    fn congruences() {
+        let combine = |a: u64, m: u64, b: u64, n: u64| {
+            combine(&(Number::from(a), Number::from(m)), b, n)
+                .map(|(r, m)| (r.to_string(), m.to_string()))
+        };
+        let small = |r: u64, m: u64| Some((r.to_string(), m.to_string()));
+
+        assert_eq!(combine(2, 3, 3, 5), small(8, 15));
+        assert_eq!(combine(1, 4, 3, 6), small(9, 12));
+        assert_eq!(combine(1, 4, 2, 6), None);
+        assert_eq!(combine(0, 1, 5, 7), small(5, 7));
+        assert_eq!(combine(4, 6, 0, 3), None);
+        assert_eq!(combine(4, 6, 1, 3), small(4, 6));
+    }
+
+    #[test]
+    fn periods_beyond_u64() {
+        // Ghosts that, as in the puzzle, first reach a goal after exactly one period, with
+        // coprime periods whose product is about 2^80.
+        let periods = [1_048_573, 1_048_571, 1_048_559, 1_048_549];
+        let cycles = periods.map(|length| Cycle {
+            tail: 1,
+            length,
+            hits: vec![length],
+        });
+
+        let steps = first_common_hit(&cycles).unwrap();
+        let expected = periods.iter().fold(Number::from(1), |product, &period| {
+            product.mul(&Number::from(period))
+        });
+        assert!(matches!(steps, Number::Big(_)));
+        assert_eq!(steps, expected);
+        assert_eq!(steps.to_string(), "1208865868604581680782053");
+    }
+
+    #[test]
+    fn offsets_beyond_u64() {
+        // Each ghost is on a goal a few steps before the end of its cycle, so the answer is some
+        // residue that has to be found with big integers.
+        let periods = [4_294_967_291, 4_294_967_279, 4_294_967_231];
+        let cycles = periods.map(|length| Cycle {
+            tail: 0,
+            length,
+            hits: vec![length - 3, length - 1],
+        });
+
+        let steps = first_common_hit(&cycles).unwrap();
+        assert!(matches!(steps, Number::Big(_)));
+        for cycle in &cycles {
+            let offset = steps.rem(cycle.length);
+            assert!(cycle.hits.contains(&offset));
+        }
+    }
+
+    #[test]
+    fn hits_in_the_tail() {
+        let cycle = Cycle {
+            tail: 5,
+            length: 3,
+            hits: vec![2, 6],
+        };
+        let other = Cycle {
+            tail: 0,
+            length: 2,
+            hits: vec![0],
+        };
+
+        assert!(cycle.at_goal(2));
+        assert!(!cycle.at_goal(3));
+        assert!(cycle.at_goal(9));
+        assert_eq!(
+            first_common_hit(&[cycle.clone(), other]),
+            Some(Number::from(2))
+        );
+        assert_eq!(first_common_hit(&[cycle]), Some(Number::from(2)));
+    }
+}
@@ -0,0 +1,133 @@
+//! Draws the network in Graphviz's DOT language, optionally with the paths walked over it.
+
+use std::fmt::Write;
+
+use crate::cycles::tail_and_length;
+use crate::network::Network;
+
+/// Colours for the paths, one per ghost, reused if there are more ghosts than colours.
+const COLOURS: [&str; 6] = ["red", "blue", "darkgreen", "orange", "purple", "brown"];
+
+/// The nodes visited walking from `start`, ending at the first goal if `goals` is given, and
+/// otherwise once the walk has gone round its cycle once.
+pub fn path(network: &Network, start: usize, goals: Option<&[bool]>) -> Vec<usize> {
+    let (tail, length) = tail_and_length(start, network);
+    let mut nodes = vec![start];
+    let mut node = start;
+
+    for step in 0..tail + length {
+        node = network.next(node, (step % network.instructions() as u64) as usize);
+        nodes.push(node);
+        if goals.is_some_and(|goals| goals[node]) {
+            break;
+        }
+    }
+    nodes
+}
+
+pub fn dot(network: &Network, starts: &[bool], goals: &[bool], paths: &[Vec<usize>]) -> String {
+    let mut dot = String::from("digraph network {\n");
+
+    for node in 0..network.len() {
+        let style = match (starts[node], goals[node]) {
+            (true, true) => " [shape=doubleoctagon, style=filled, fillcolor=gold]",
+            (true, false) => " [shape=box, style=filled, fillcolor=palegreen]",
+            (false, true) => " [shape=doublecircle, style=filled, fillcolor=lightcoral]",
+            (false, false) => "",
+        };
+        writeln!(dot, "    \"{}\"{style};", network.name(node)).unwrap();
+    }
+
+    for node in 0..network.len() {
+        for (label, next) in ["L", "R"].into_iter().zip(network.neighbours(node)) {
+            writeln!(
+                dot,
+                "    \"{}\" -> \"{}\" [label=\"{label}\", color=gray];",
+                network.name(node),
+                network.name(next)
+            )
+            .unwrap();
+        }
+    }
+
+    for (ghost, path) in paths.iter().enumerate() {
+        let colour = COLOURS[ghost % COLOURS.len()];
+        let mut edges = path
+            .windows(2)
+            .map(|pair| (pair[0], pair[1]))
+            .collect::<Vec<_>>();
+        edges.sort_unstable();
+        edges.dedup();
+
+        for (from, to) in edges {
+            writeln!(
+                dot,
+                "    \"{}\" -> \"{}\" [color={colour}, penwidth=2];",
+                network.name(from),
+                network.name(to)
+            )
+            .unwrap();
+        }
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::load;
+
+    #[test]
+    fn paths() {
+        let network = load(include_str!("../ghosts.txt")).unwrap();
+        let names = |path: Vec<usize>| {
+            path.into_iter()
+                .map(|node| network.name(node))
+                .collect::<Vec<_>>()
+        };
+        let start = |name| network.index(name).unwrap();
+        let goals = network.select(|name| name.ends_with('Z'));
+
+        assert_eq!(
+            names(path(&network, start("11A"), Some(&goals))),
+            ["11A", "11B", "11Z"]
+        );
+        assert_eq!(
+            names(path(&network, start("11A"), None)),
+            ["11A", "11B", "11Z", "11B"]
+        );
+        assert_eq!(
+            names(path(&network, start("22A"), None)),
+            ["22A", "22B", "22C", "22Z", "22B", "22C", "22Z", "22B"]
+        );
+    }
+
+    #[test]
+    fn example() {
+        let network = load(include_str!("../example2.txt")).unwrap();
+        let starts = network.select(|name| name == "AAA");
+        let goals = network.select(|name| name == "ZZZ");
+        let walk = path(&network, 0, Some(&goals));
+
+        assert_eq!(
+            dot(&network, &starts, &goals, &[walk]),
+            "digraph network {
+    \"AAA\" [shape=box, style=filled, fillcolor=palegreen];
+    \"BBB\";
+    \"ZZZ\" [shape=doublecircle, style=filled, fillcolor=lightcoral];
+    \"AAA\" -> \"BBB\" [label=\"L\", color=gray];
+    \"AAA\" -> \"BBB\" [label=\"R\", color=gray];
+    \"BBB\" -> \"AAA\" [label=\"L\", color=gray];
+    \"BBB\" -> \"ZZZ\" [label=\"R\", color=gray];
+    \"ZZZ\" -> \"ZZZ\" [label=\"L\", color=gray];
+    \"ZZZ\" -> \"ZZZ\" [label=\"R\", color=gray];
+    \"AAA\" -> \"BBB\" [color=red, penwidth=2];
+    \"BBB\" -> \"AAA\" [color=red, penwidth=2];
+    \"BBB\" -> \"ZZZ\" [color=red, penwidth=2];
+}
+"
+        );
+    }
+}
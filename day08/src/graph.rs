@@ -0,0 +1,218 @@
+//! Facts about the network as a graph, where each step may go left or right freely rather than
+//! following the instructions.
+
+use std::collections::VecDeque;
+
+use crate::network::Network;
+use crate::Direction;
+
+/// The fewest choices of left or right that lead from `from` to `to`, or `None` if `to` can't be
+/// reached.
+pub fn shortest_path(network: &Network, from: usize, to: usize) -> Option<Vec<Direction>> {
+    // How each node was first reached, from which node and in which direction.
+    let mut previous = vec![None; network.len()];
+    let mut queue = VecDeque::from([from]);
+    let mut seen = vec![false; network.len()];
+    seen[from] = true;
+
+    while let Some(node) = queue.pop_front() {
+        if node == to {
+            let mut path = Vec::new();
+            let mut node = to;
+            while let Some((parent, direction)) = previous[node] {
+                path.push(direction);
+                node = parent;
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        let [left, right] = network.neighbours(node);
+        for (next, direction) in [(left, Direction::Left), (right, Direction::Right)] {
+            if !seen[next] {
+                seen[next] = true;
+                previous[next] = Some((node, direction));
+                queue.push_back(next);
+            }
+        }
+    }
+
+    None
+}
+
+/// Every node that can be reached from `start`, including `start` itself, in the order they are
+/// first found.
+pub fn reachable(network: &Network, start: usize) -> Vec<usize> {
+    let mut seen = vec![false; network.len()];
+    let mut stack = vec![start];
+    let mut nodes = Vec::new();
+
+    while let Some(node) = stack.pop() {
+        if !seen[node] {
+            seen[node] = true;
+            nodes.push(node);
+            stack.extend(network.neighbours(node));
+        }
+    }
+    nodes
+}
+
+/// The strongly connected components of the network, found with Tarjan's algorithm. Each
+/// component's nodes are sorted, and a component comes before any that lead to it.
+pub fn components(network: &Network) -> Vec<Vec<usize>> {
+    const UNVISITED: usize = usize::MAX;
+
+    let mut index = vec![UNVISITED; network.len()];
+    let mut low = vec![0; network.len()];
+    let mut on_stack = vec![false; network.len()];
+    let mut stack = Vec::new();
+    let mut next_index = 0;
+    let mut components = Vec::new();
+
+    for root in 0..network.len() {
+        if index[root] != UNVISITED {
+            continue;
+        }
+
+        // Each frame holds a node being visited and how many of its neighbours have been
+        // explored, so that long chains of nodes don't overflow the call stack.
+        let mut frames = vec![(root, 0)];
+        index[root] = next_index;
+        low[root] = next_index;
+        next_index += 1;
+        stack.push(root);
+        on_stack[root] = true;
+
+        while let Some(&(node, explored)) = frames.last() {
+            if explored < 2 {
+                frames.last_mut().unwrap().1 += 1;
+
+                let next = network.neighbours(node)[explored];
+                if index[next] == UNVISITED {
+                    index[next] = next_index;
+                    low[next] = next_index;
+                    next_index += 1;
+                    stack.push(next);
+                    on_stack[next] = true;
+                    frames.push((next, 0));
+                } else if on_stack[next] {
+                    low[node] = low[node].min(index[next]);
+                }
+                continue;
+            }
+
+            frames.pop();
+            if let Some(&(parent, _)) = frames.last() {
+                low[parent] = low[parent].min(low[node]);
+            }
+            if low[node] == index[node] {
+                let mut component = Vec::new();
+                loop {
+                    let member = stack.pop().unwrap();
+                    on_stack[member] = false;
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                component.sort_unstable();
+                components.push(component);
+            }
+        }
+    }
+
+    components
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::load;
+
+    fn compile(input: &'static str) -> Network {
+        load(input).unwrap()
+    }
+
+    fn names(network: &Network, nodes: &[usize]) -> Vec<&'static str> {
+        let mut names = nodes
+            .iter()
+            .map(|&node| network.name(node))
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+        names
+    }
+
+    #[test]
+    fn shortest_paths() {
+        let network = compile(include_str!("../example.txt"));
+        let node = |name| network.index(name).unwrap();
+
+        assert_eq!(
+            shortest_path(&network, node("AAA"), node("ZZZ")),
+            Some(vec![Direction::Right, Direction::Left])
+        );
+        assert_eq!(
+            shortest_path(&network, node("AAA"), node("EEE")),
+            Some(vec![Direction::Left, Direction::Right])
+        );
+        assert_eq!(
+            shortest_path(&network, node("AAA"), node("AAA")),
+            Some(vec![])
+        );
+        assert_eq!(shortest_path(&network, node("DDD"), node("AAA")), None);
+
+        // Following the instructions takes six steps, but two free choices are enough.
+        let network = compile(include_str!("../example2.txt"));
+        let node = |name| network.index(name).unwrap();
+        assert_eq!(
+            shortest_path(&network, node("AAA"), node("ZZZ")).map(|path| path.len()),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn reachable_nodes() {
+        let network = compile(include_str!("../example.txt"));
+        let node = |name| network.index(name).unwrap();
+
+        assert_eq!(names(&network, &reachable(&network, node("AAA"))).len(), 7);
+        assert_eq!(
+            names(&network, &reachable(&network, node("BBB"))),
+            ["BBB", "DDD", "EEE"]
+        );
+        assert_eq!(names(&network, &reachable(&network, node("ZZZ"))), ["ZZZ"]);
+    }
+
+    #[test]
+    fn strongly_connected_components() {
+        let components_by_name = |network: &Network| {
+            let mut components = components(network)
+                .iter()
+                .map(|component| names(network, component))
+                .collect::<Vec<_>>();
+            components.sort_unstable();
+            components
+        };
+
+        let network = compile(include_str!("../ghosts.txt"));
+        assert_eq!(
+            components_by_name(&network),
+            [
+                vec!["11A"],
+                vec!["11B", "11Z"],
+                vec!["22A"],
+                vec!["22B", "22C", "22Z"],
+                vec!["XXX"],
+            ]
+        );
+
+        let network = compile(include_str!("../example2.txt"));
+        let components = components(&network);
+        assert_eq!(names(&network, &components[0]), ["ZZZ"]);
+        assert_eq!(names(&network, &components[1]), ["AAA", "BBB"]);
+        assert_eq!(
+            components_by_name(&compile(include_str!("../example.txt"))).len(),
+            7
+        );
+    }
+}
@@ -1,41 +1,150 @@
-use std::collections::HashMap;
+mod bench;
+mod bignum;
+mod cycles;
+mod dot;
+mod graph;
+mod network;
+mod pattern;
+mod rng;
+mod simulator;
+mod validate;
 
-fn parse(
-    input: &'static str,
-) -> (
-    impl Iterator<Item = Direction> + Clone,
-    HashMap<&'static str, Node>,
-) {
-    let (instructions, network) = input.split_once("\n\n").unwrap();
-
-    let instructions = instructions.chars().map(|char| match char {
-        'L' => Direction::Left,
-        'R' => Direction::Right,
-        _ => panic!("Invalid direction"),
-    });
+use std::{collections::HashMap, fmt};
+
+use bignum::Number;
+use cycles::{first_common_hit, Cycle};
+use network::{Network, UndefinedNode, WalkError};
+use pattern::Pattern;
+use simulator::Simulator;
+use validate::validate;
+
+/// Why the input couldn't be parsed.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+enum ParseError {
+    EmptyInstructions,
+    InvalidDirection {
+        column: usize,
+        found: char,
+    },
+    /// The second line, between the instructions and the network, isn't blank.
+    MissingBlankLine,
+    MalformedDefinition {
+        line: usize,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::EmptyInstructions => write!(f, "line 1: there are no instructions"),
+            ParseError::InvalidDirection { column, found } => {
+                write!(f, "line 1, column {column}: `{found}` is not L or R")
+            }
+            ParseError::MissingBlankLine => {
+                write!(f, "line 2: expected a blank line after the instructions")
+            }
+            ParseError::MalformedDefinition { line } => {
+                write!(f, "line {line}: expected a node like `AAA = (BBB, CCC)`")
+            }
+        }
+    }
+}
 
-    let network = network.split('\n').filter(|&node| node != "").fold(
-        HashMap::<&'static str, Node>::default(),
-        |mut map, node| {
-            let (key, value) = node.split_once(" = ").unwrap();
+impl std::error::Error for ParseError {}
 
+fn parse(input: &'static str) -> Result<(Vec<Direction>, HashMap<&'static str, Node>), ParseError> {
+    let network = definitions(input)
+        .map(|definition| definition.map(|(_, name, node)| (name, node)))
+        .collect::<Result<_, _>>()?;
+
+    Ok((instructions(input)?, network))
+}
+
+/// Why the input couldn't be turned into a network.
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum LoadError {
+    Parse(ParseError),
+    Undefined(UndefinedNode),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Parse(error) => write!(f, "{error}"),
+            LoadError::Undefined(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+impl From<ParseError> for LoadError {
+    fn from(error: ParseError) -> Self {
+        LoadError::Parse(error)
+    }
+}
+
+impl From<UndefinedNode> for LoadError {
+    fn from(error: UndefinedNode) -> Self {
+        LoadError::Undefined(error)
+    }
+}
+
+/// Parses the input and compiles the network it describes.
+fn load(input: &'static str) -> Result<Network, LoadError> {
+    let (instructions, nodes) = parse(input)?;
+    Ok(Network::compile(&instructions, &nodes)?)
+}
+
+/// The instructions on the first line, which must not be empty.
+fn instructions(input: &'static str) -> Result<Vec<Direction>, ParseError> {
+    let line = input.lines().next().unwrap_or("");
+    if line.is_empty() {
+        return Err(ParseError::EmptyInstructions);
+    }
+
+    line.chars()
+        .enumerate()
+        .map(|(i, char)| match char {
+            'L' => Ok(Direction::Left),
+            'R' => Ok(Direction::Right),
+            _ => Err(ParseError::InvalidDirection {
+                column: i + 1,
+                found: char,
+            }),
+        })
+        .collect()
+}
+
+/// Each node defined in the input, with the number of the line defining it. A node defined more
+/// than once is listed each time, and anything on the second line is an error.
+fn definitions(
+    input: &'static str,
+) -> impl Iterator<Item = Result<(usize, &'static str, Node), ParseError>> {
+    input
+        .lines()
+        .enumerate()
+        .skip(1)
+        .filter(|(_, node)| !node.is_empty())
+        .map(|(index, node)| {
+            let line = index + 1;
+            if line == 2 {
+                return Err(ParseError::MissingBlankLine);
+            }
+            let (key, value) = node
+                .split_once(" = ")
+                .ok_or(ParseError::MalformedDefinition { line })?;
             let (left, right) = value
                 .strip_prefix("(")
-                .unwrap()
-                .strip_suffix(")")
-                .unwrap()
-                .split_once(", ")
-                .unwrap();
-
-            map.insert(key, Node { left, right });
-            map
-        },
-    );
+                .and_then(|value| value.strip_suffix(")"))
+                .and_then(|value| value.split_once(", "))
+                .ok_or(ParseError::MalformedDefinition { line })?;
 
-    (instructions, network)
+            Ok((line, key, Node { left, right }))
+        })
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
 enum Direction {
     Left,
     Right,
@@ -47,78 +156,303 @@ struct Node {
     right: &'static str,
 }
 
-fn part_1(input: &'static str) -> i32 {
-    let (instructions, network) = parse(input);
-    let mut instructions = instructions.cycle();
+/// The fewest steps from any start to any goal, giving up after `limit` steps.
+fn fewest_steps(
+    network: &Network,
+    starts: &Pattern,
+    goals: &Pattern,
+    limit: u64,
+) -> Result<u64, WalkError> {
+    let goals = network.select(|node| goals.matches(node));
+    network.steps_to(
+        &network.matching(|node| starts.matches(node)),
+        &goals,
+        limit,
+    )
+}
 
-    let mut node = network.get("AAA").unwrap();
-    let mut steps = 0;
-    loop {
-        steps += 1;
-        let next = match instructions.next().unwrap() {
-            Direction::Left => node.left,
-            Direction::Right => node.right,
-        };
+/// The first step at which a ghost setting off from every start is on a goal at the same time, or
+/// `None` if they never are.
+fn together(network: &Network, starts: &Pattern, goals: &Pattern) -> Option<Number> {
+    let goals = network.select(|node| goals.matches(node));
+    let cycles = network
+        .matching(|node| starts.matches(node))
+        .into_iter()
+        .map(|start| Cycle::analyse(start, network, &goals))
+        .collect::<Vec<_>>();
 
-        if next == "ZZZ" {
-            break;
-        }
+    first_common_hit(&cycles)
+}
 
-        node = network.get(next).unwrap();
-    }
+fn part_1(network: &Network) -> Result<u64, WalkError> {
+    let [starts, goals] = ["AAA", "ZZZ"].map(|name| Pattern::Exact(name.to_string()));
+    fewest_steps(network, &starts, &goals, u64::MAX)
+}
+
+fn part_2(network: &Network) -> Option<Number> {
+    let [starts, goals] = ["A", "Z"].map(|suffix| Pattern::Suffix(suffix.to_string()));
+    together(network, &starts, &goals)
+}
+
+fn steps_or_never(steps: Option<Number>) -> String {
+    steps.map_or("never".to_string(), |steps| steps.to_string())
+}
 
-    steps
+/// Prints any problems with the network, and exits if it can't be walked at all. Otherwise
+/// returns the compiled network.
+fn check(input: &'static str, walks: &[(&Pattern, &Pattern)]) -> Network {
+    let problems = validate(input, walks);
+    for problem in &problems {
+        let severity = if problem.is_error() {
+            "error"
+        } else {
+            "warning"
+        };
+        eprintln!("{severity}: {problem}");
+    }
+    if problems.iter().any(|problem| problem.is_error()) {
+        std::process::exit(1);
+    }
+    or_exit(load(input))
 }
 
-fn part_2(input: &'static str) -> u64 {
-    let (instructions, network) = parse(input);
-    let mut instructions = instructions.cycle();
+/// Draws the network with starts and goals highlighted, and optionally the path walked from each
+/// start: to the first goal for `walk`, or round the first cycle for `ghosts`.
+fn draw(network: &Network, overlay: Option<&str>, starts: &Pattern, goals: &Pattern) -> String {
+    let starts = network.select(|node| starts.matches(node));
+    let goals = network.select(|node| goals.matches(node));
 
-    let starts = network
-        .keys()
-        .filter(|&key| key.ends_with('A'))
-        .copied()
+    let paths = (0..network.len())
+        .filter(|&node| starts[node])
+        .filter_map(|start| match overlay {
+            Some("walk") => Some(dot::path(network, start, Some(&goals))),
+            Some("ghosts") => Some(dot::path(network, start, None)),
+            _ => None,
+        })
         .collect::<Vec<_>>();
 
-    let steps = starts
-        .into_iter()
-        .map(|start| {
-            let mut steps = 0;
+    dot::dot(network, &starts, &goals, &paths)
+}
 
-            let mut node = network.get(start).unwrap();
-            loop {
-                steps += 1;
+/// Describes how `from` and `to` are connected when every step may go either way.
+fn connections(network: &Network, from: &str, to: &str) -> String {
+    let [from, to] = [from, to].map(|name| {
+        or_exit(
+            network
+                .index(name)
+                .ok_or_else(|| format!("{name} is not defined")),
+        )
+    });
 
-                let next = match instructions.next().unwrap() {
-                    Direction::Left => node.left,
-                    Direction::Right => node.right,
-                };
+    let path = match graph::shortest_path(network, from, to) {
+        Some(path) => path
+            .iter()
+            .map(|direction| match direction {
+                Direction::Left => 'L',
+                Direction::Right => 'R',
+            })
+            .collect::<String>(),
+        None => "none".to_string(),
+    };
+    let components = graph::components(network);
+    let largest = components.iter().map(Vec::len).max().unwrap_or(0);
 
-                if next.ends_with('Z') {
-                    break;
-                }
+    format!(
+        "Shortest path: {path}\n\
+         Reachable from {}: {} of {} nodes\n\
+         Strongly connected components: {} (largest has {largest} nodes)",
+        network.name(from),
+        graph::reachable(network, from).len(),
+        network.len(),
+        components.len(),
+    )
+}
 
-                node = network.get(next).unwrap();
-            }
-            steps
-        })
-        .collect::<Vec<_>>();
+fn or_exit<T, E: std::fmt::Display>(result: Result<T, E>) -> T {
+    result.unwrap_or_else(|error| {
+        eprintln!("{error}");
+        std::process::exit(1);
+    })
+}
 
-    let gcd = |mut a, mut b| {
-        while b != 0 {
-            let temp = b;
-            b = a % b;
-            a = temp;
+/// Prints where every ghost is after each step, until they are all on a goal or `limit` steps have
+/// been taken.
+fn simulate(network: &Network, starts: &Pattern, goals: &Pattern, limit: u64) {
+    let is_goal = |node: &str| goals.matches(node);
+    let mut simulator = Simulator::new(network.matching(|node| starts.matches(node)), network);
+    loop {
+        let positions = simulator.positions().collect::<Vec<_>>();
+        println!("{:>6}: {}", simulator.steps(), positions.join(" "));
+        if simulator.steps() > 0 && simulator.all_at_end(is_goal) || simulator.steps() >= limit {
+            break;
         }
-        a
+        simulator.step();
+    }
+}
+
+fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let pattern = |index: usize, default: &str| {
+        or_exit(
+            args.get(index)
+                .map_or(default, String::as_str)
+                .parse::<Pattern>(),
+        )
     };
+    let input = include_str!("input.txt");
 
-    steps
-        .iter()
-        .fold(1, |lcm, &step| lcm * step / gcd(lcm, step))
+    match args.first().map(String::as_str) {
+        Some("walk") => {
+            let (starts, goals) = (pattern(1, "AAA"), pattern(2, "ZZZ"));
+            let limit = args.get(3).map_or(u64::MAX, |limit| limit.parse().unwrap());
+            let network = check(input, &[(&starts, &goals)]);
+            let steps = or_exit(fewest_steps(&network, &starts, &goals, limit));
+            println!("Steps: {steps}");
+        }
+        Some("ghosts") => {
+            let (starts, goals) = (pattern(1, "suffix:A"), pattern(2, "suffix:Z"));
+            let network = check(input, &[(&starts, &goals)]);
+            println!(
+                "Steps: {}",
+                steps_or_never(together(&network, &starts, &goals))
+            );
+        }
+        Some("simulate") => {
+            let limit = args.get(1).map_or(100, |limit| limit.parse().unwrap());
+            let (starts, goals) = (pattern(2, "suffix:A"), pattern(3, "suffix:Z"));
+            let network = check(input, &[(&starts, &goals)]);
+            simulate(&network, &starts, &goals, limit);
+        }
+        Some("dot") => {
+            let overlay = args.get(1).map(String::as_str);
+            let (starts, goals) = match overlay {
+                Some("walk") => (pattern(2, "AAA"), pattern(3, "ZZZ")),
+                _ => (pattern(2, "suffix:A"), pattern(3, "suffix:Z")),
+            };
+            let network = check(input, &[]);
+            print!("{}", draw(&network, overlay, &starts, &goals));
+        }
+        Some("graph") => {
+            let from = args.get(1).map_or("AAA", String::as_str);
+            let to = args.get(2).map_or("ZZZ", String::as_str);
+            let network = check(input, &[]);
+            println!("{}", connections(&network, from, to));
+        }
+        Some("bench") => {
+            let nodes = args.get(1).map_or(100_000, |nodes| nodes.parse().unwrap());
+            let steps = args
+                .get(2)
+                .map_or(10_000_000, |steps| steps.parse().unwrap());
+            bench::run(nodes, steps);
+        }
+        _ => {
+            let network = check(input, &[]);
+            println!("Part 1: {}", or_exit(part_1(&network)));
+            println!("Part 2: {}", steps_or_never(part_2(&network)));
+        }
+    }
 }
 
-fn main() {
-    println!("Part 1: {}", part_1(include_str!("input.txt")));
-    println!("Part 2: {}", part_2(include_str!("input.txt")));
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn example() {
+        assert_eq!(
+            part_1(&load(include_str!("../example.txt")).unwrap()),
+            Ok(2)
+        );
+        assert_eq!(
+            part_1(&load(include_str!("../example2.txt")).unwrap()),
+            Ok(6)
+        );
+        assert_eq!(
+            part_2(&load(include_str!("../ghosts.txt")).unwrap()),
+            Some(Number::from(6))
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error = |input| parse(input).unwrap_err();
+
+        assert_eq!(
+            error("\n\nAAA = (AAA, AAA)\n"),
+            ParseError::EmptyInstructions
+        );
+        assert_eq!(
+            error("LRl\n\nAAA = (AAA, AAA)\n").to_string(),
+            "line 1, column 3: `l` is not L or R"
+        );
+        assert_eq!(
+            error("LR\n\nAAA = (AAA, AAA)\nBBB = (AAA AAA)\n"),
+            ParseError::MalformedDefinition { line: 4 }
+        );
+        assert_eq!(
+            error("LR\nAAA = (ZZZ, ZZZ)\nZZZ = (ZZZ, ZZZ)\n").to_string(),
+            "line 2: expected a blank line after the instructions"
+        );
+        assert_eq!(
+            error("LR\n\nAAA = (AAA, AAA\n"),
+            ParseError::MalformedDefinition { line: 3 }
+        );
+        assert_eq!(
+            load("LR\n\nAAA = (BBB, BBB)\n").err(),
+            Some(LoadError::Undefined(UndefinedNode {
+                node: "AAA",
+                reference: "BBB"
+            }))
+        );
+    }
+
+    #[test]
+    fn patterns() {
+        let network = load(include_str!("../ghosts.txt")).unwrap();
+        let pattern = |pattern: &str| pattern.parse::<Pattern>().unwrap();
+
+        let fewest_steps =
+            |starts, goals| fewest_steps(&network, &pattern(starts), &pattern(goals), u64::MAX);
+
+        assert_eq!(fewest_steps("22A", "suffix:Z"), Ok(3));
+        assert_eq!(fewest_steps("prefix:22", "22Z"), Ok(1));
+        assert_eq!(fewest_steps("glob:?1?", "regex:^(XX|11)[BZ]$"), Ok(1));
+        assert_eq!(
+            fewest_steps("XXX", "glob:*Z"),
+            Err(WalkError::Cycle {
+                start: "XXX",
+                node: "XXX",
+                first: 0,
+                steps: 2
+            })
+        );
+        assert_eq!(fewest_steps("YYY", "ZZZ"), Err(WalkError::NoStarts));
+        assert_eq!(
+            together(&network, &pattern("regex:A$"), &pattern("glob:*Z")),
+            Some(Number::from(6))
+        );
+        assert_eq!(
+            together(&network, &pattern("suffix:A"), &pattern("regex:[BZ]$")),
+            Some(Number::from(1))
+        );
+    }
+
+    #[test]
+    fn ghosts_with_tails() {
+        // The first ghost is on a goal at every odd step and the second at 2, 5, 8, ..., so the
+        // LCM of their first arrivals (2) is wrong.
+        let input = "L\n\n\
+            1A = (1Z, 1Z)\n1Z = (1B, 1B)\n1B = (1Z, 1Z)\n\
+            2A = (2B, 2B)\n2B = (2Z, 2Z)\n2Z = (2C, 2C)\n2C = (2D, 2D)\n2D = (2Z, 2Z)\n";
+        assert_eq!(part_2(&load(input).unwrap()), Some(Number::from(5)));
+    }
+
+    #[test]
+    fn ghosts_that_never_meet() {
+        // The first ghost is on a goal at every odd step and the second at every even step.
+        let input = "L\n\n\
+            1A = (1Z, 1Z)\n1Z = (1B, 1B)\n1B = (1Z, 1Z)\n\
+            2A = (2B, 2B)\n2B = (2Z, 2Z)\n2Z = (2B, 2B)\n";
+        assert_eq!(part_2(&load(input).unwrap()), None);
+    }
 }
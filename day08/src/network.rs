@@ -0,0 +1,333 @@
+//! The network compiled to dense node indices, so that walking it never hashes a node's name.
+
+use std::{collections::HashMap, fmt};
+
+use crate::{Direction, Node};
+
+/// Why a walk didn't reach a goal.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum WalkError {
+    NoStarts,
+    /// The walk is back at `node` at the start of the instructions, where it also was after
+    /// `first` steps, without having passed a goal in between, so it will go round forever.
+    Cycle {
+        start: &'static str,
+        node: &'static str,
+        first: u64,
+        steps: u64,
+    },
+    StepLimit {
+        start: &'static str,
+        limit: u64,
+    },
+}
+
+impl fmt::Display for WalkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WalkError::NoStarts => write!(f, "no node matches the starts"),
+            WalkError::Cycle {
+                start,
+                node,
+                first,
+                steps,
+            } => write!(
+                f,
+                "the walk from {start} never reaches a goal: it is at {node} at the start of the \
+                 instructions after {first} steps and again after {steps} steps"
+            ),
+            WalkError::StepLimit { start, limit } => write!(
+                f,
+                "the walk from {start} didn't reach a goal within {limit} steps"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for WalkError {}
+
+/// `node` leads to `reference`, which is never defined.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct UndefinedNode {
+    pub node: &'static str,
+    pub reference: &'static str,
+}
+
+impl fmt::Display for UndefinedNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} leads to {}, which is never defined",
+            self.node, self.reference
+        )
+    }
+}
+
+impl std::error::Error for UndefinedNode {}
+
+pub struct Network {
+    /// Every node's name, sorted so that indices don't depend on the order of the input and
+    /// can be found by binary search.
+    names: Vec<&'static str>,
+    left: Vec<usize>,
+    right: Vec<usize>,
+    instructions: Vec<Direction>,
+    /// Where each node leads after following every instruction once.
+    block: Vec<usize>,
+}
+
+impl Network {
+    pub fn compile(
+        instructions: &[Direction],
+        nodes: &HashMap<&'static str, Node>,
+    ) -> Result<Self, UndefinedNode> {
+        let mut names = nodes.keys().copied().collect::<Vec<_>>();
+        names.sort_unstable();
+
+        let index = |node: &'static str, reference| {
+            names
+                .binary_search(&reference)
+                .map_err(|_| UndefinedNode { node, reference })
+        };
+        let left = names
+            .iter()
+            .map(|&name| index(name, nodes[name].left))
+            .collect::<Result<_, _>>()?;
+        let right = names
+            .iter()
+            .map(|&name| index(name, nodes[name].right))
+            .collect::<Result<_, _>>()?;
+
+        let mut network = Self {
+            names,
+            left,
+            right,
+            instructions: instructions.to_vec(),
+            block: Vec::new(),
+        };
+        network.block = (0..network.len())
+            .map(|node| {
+                (0..instructions.len()).fold(node, |node, cursor| network.next(node, cursor))
+            })
+            .collect();
+        Ok(network)
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn instructions(&self) -> usize {
+        self.instructions.len()
+    }
+
+    pub fn name(&self, node: usize) -> &'static str {
+        self.names[node]
+    }
+
+    pub fn index(&self, name: &str) -> Option<usize> {
+        self.names.binary_search(&name).ok()
+    }
+
+    /// The nodes whose names match `predicate`.
+    pub fn matching(&self, predicate: impl Fn(&str) -> bool) -> Vec<usize> {
+        (0..self.len())
+            .filter(|&node| predicate(self.names[node]))
+            .collect()
+    }
+
+    /// Whether each node's name matches `predicate`, indexed by node.
+    pub fn select(&self, predicate: impl Fn(&str) -> bool) -> Vec<bool> {
+        self.names.iter().map(|name| predicate(name)).collect()
+    }
+
+    /// The nodes to the left and right of `node`.
+    pub fn neighbours(&self, node: usize) -> [usize; 2] {
+        [self.left[node], self.right[node]]
+    }
+
+    /// The node reached from `node` by following the instruction at `cursor`.
+    pub fn next(&self, node: usize, cursor: usize) -> usize {
+        match self.instructions[cursor] {
+            Direction::Left => self.left[node],
+            Direction::Right => self.right[node],
+        }
+    }
+
+    /// The node reached after `steps` steps from `node`, starting with the first instruction.
+    pub fn walk(&self, mut node: usize, steps: u64) -> usize {
+        let blocks = steps / self.instructions.len() as u64;
+        for _ in 0..blocks {
+            node = self.block[node];
+        }
+        for cursor in 0..(steps % self.instructions.len() as u64) as usize {
+            node = self.next(node, cursor);
+        }
+        node
+    }
+
+    /// For each node, the first step of a block of instructions starting there that lands on a
+    /// goal, if any does.
+    fn goals_in_block(&self, goals: &[bool]) -> Vec<Option<u64>> {
+        (0..self.len())
+            .map(|mut node| {
+                (0..self.instructions.len()).find_map(|cursor| {
+                    node = self.next(node, cursor);
+                    goals[node].then_some(cursor as u64 + 1)
+                })
+            })
+            .collect()
+    }
+
+    /// The fewest steps (at least one) from any of `starts` to a goal, jumping a whole block of
+    /// instructions at a time where no goal is passed. If no start reaches a goal within `limit`
+    /// steps, the first start's error is returned.
+    pub fn steps_to(&self, starts: &[usize], goals: &[bool], limit: u64) -> Result<u64, WalkError> {
+        let first = self.goals_in_block(goals);
+
+        let mut result: Result<u64, _> = Err(WalkError::NoStarts);
+        for &start in starts {
+            let steps = self.steps_from(start, &first, limit);
+            result = match (result, steps) {
+                (Ok(best), Ok(steps)) => Ok(best.min(steps)),
+                (Ok(best), Err(_)) => Ok(best),
+                (Err(WalkError::NoStarts), steps) | (Err(_), steps @ Ok(_)) => steps,
+                (Err(error), Err(_)) => Err(error),
+            };
+        }
+        result
+    }
+
+    fn steps_from(
+        &self,
+        start: usize,
+        first: &[Option<u64>],
+        limit: u64,
+    ) -> Result<u64, WalkError> {
+        let start_name = self.names[start];
+        // Block boundaries always fall on the first instruction, so a walk that is back at a node
+        // it was at on an earlier boundary will go round in circles from then on.
+        let mut seen = vec![None; self.len()];
+        let mut node = start;
+        let mut steps = 0;
+
+        loop {
+            if let Some(step) = first[node] {
+                return match steps + step {
+                    steps if steps <= limit => Ok(steps),
+                    _ => Err(WalkError::StepLimit {
+                        start: start_name,
+                        limit,
+                    }),
+                };
+            }
+            if let Some(first) = seen[node] {
+                return Err(WalkError::Cycle {
+                    start: start_name,
+                    node: self.names[node],
+                    first,
+                    steps,
+                });
+            }
+            seen[node] = Some(steps);
+
+            steps += self.instructions.len() as u64;
+            if steps >= limit {
+                return Err(WalkError::StepLimit {
+                    start: start_name,
+                    limit,
+                });
+            }
+            node = self.block[node];
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{load, parse};
+
+    #[test]
+    fn compiled() {
+        let network = load(include_str!("../example2.txt")).unwrap();
+        let [aaa, bbb, zzz] = ["AAA", "BBB", "ZZZ"].map(|name| network.index(name).unwrap());
+
+        assert_eq!(network.len(), 3);
+        assert_eq!(network.name(bbb), "BBB");
+        assert_eq!(network.index("CCC"), None);
+        assert_eq!(network.block, [bbb, zzz, zzz]);
+        assert_eq!(
+            network.select(|name| name.ends_with('B')),
+            [false, true, false]
+        );
+        assert_eq!(network.matching(|name| name != "BBB"), [aaa, zzz]);
+
+        let goals = network.select(|name| name == "ZZZ");
+        assert_eq!(network.steps_to(&[aaa], &goals, u64::MAX), Ok(6));
+        assert_eq!(network.steps_to(&[aaa, bbb], &goals, u64::MAX), Ok(3));
+        assert_eq!(
+            network.steps_to(&[], &goals, u64::MAX),
+            Err(WalkError::NoStarts)
+        );
+    }
+
+    #[test]
+    fn walks_that_never_arrive() {
+        let network = load(include_str!("../example2.txt")).unwrap();
+        let [aaa, zzz] = ["AAA", "ZZZ"].map(|name| network.index(name).unwrap());
+
+        let goals = network.select(|name| name == "ZZZ");
+        assert_eq!(
+            network.steps_to(&[aaa], &goals, 5),
+            Err(WalkError::StepLimit {
+                start: "AAA",
+                limit: 5
+            })
+        );
+        assert_eq!(network.steps_to(&[aaa], &goals, 6), Ok(6));
+
+        let error = network
+            .steps_to(&[zzz], &network.select(|name| name == "AAA"), u64::MAX)
+            .unwrap_err();
+        assert_eq!(
+            error,
+            WalkError::Cycle {
+                start: "ZZZ",
+                node: "ZZZ",
+                first: 0,
+                steps: 3
+            }
+        );
+        assert_eq!(
+            network.steps_to(&[aaa], &network.select(|name| name == "AAA"), u64::MAX),
+            Ok(2)
+        );
+        assert_eq!(network.walk(aaa, 6), zzz);
+    }
+
+    #[test]
+    fn block_jumps_match_single_steps() {
+        let network = load(include_str!("../ghosts.txt")).unwrap();
+
+        for start in 0..network.len() {
+            let mut node = start;
+            for steps in 0..20 {
+                assert_eq!(network.walk(start, steps), node);
+                node = network.next(node, steps as usize % network.instructions());
+            }
+        }
+    }
+
+    #[test]
+    fn undefined_nodes() {
+        let (instructions, nodes) = parse("LR\n\nAAA = (AAA, BBB)\n").unwrap();
+        assert_eq!(
+            Network::compile(&instructions, &nodes).err(),
+            Some(UndefinedNode {
+                node: "AAA",
+                reference: "BBB"
+            })
+        );
+    }
+}
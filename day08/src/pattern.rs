@@ -0,0 +1,392 @@
+//! Patterns picking out sets of nodes by name, such as the starts and goals of a walk.
+//!
+//! A pattern is written `kind:text`, where the kind is `exact`, `prefix`, `suffix`, `glob` or
+//! `regex`. Text without a kind is matched exactly.
+
+use std::{fmt, str::FromStr};
+
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    Exact(String),
+    Prefix(String),
+    Suffix(String),
+    /// `*` matches any run of characters, `?` any one character and `[...]` any character in a
+    /// class. The whole name must match.
+    Glob(Regex),
+    /// A regular expression found anywhere in the name, unless anchored with `^` or `$`. Syntax
+    /// it doesn't support, such as `\d` or `{2}`, is an error rather than a literal.
+    Regex(Regex),
+}
+
+impl Pattern {
+    pub fn matches(&self, name: &str) -> bool {
+        match self {
+            Pattern::Exact(text) => name == text,
+            Pattern::Prefix(text) => name.starts_with(text.as_str()),
+            Pattern::Suffix(text) => name.ends_with(text.as_str()),
+            Pattern::Glob(regex) | Pattern::Regex(regex) => regex.is_match(name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PatternError {
+    pub pattern: String,
+    pub message: String,
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid pattern `{}`: {}", self.pattern, self.message)
+    }
+}
+
+impl std::error::Error for PatternError {}
+
+impl FromStr for Pattern {
+    type Err = PatternError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = |message: String| PatternError {
+            pattern: s.to_string(),
+            message,
+        };
+
+        let Some((kind, text)) = s.split_once(':') else {
+            return Ok(Pattern::Exact(s.to_string()));
+        };
+        match kind {
+            "exact" => Ok(Pattern::Exact(text.to_string())),
+            "prefix" => Ok(Pattern::Prefix(text.to_string())),
+            "suffix" => Ok(Pattern::Suffix(text.to_string())),
+            "glob" => Regex::glob(text).map(Pattern::Glob).map_err(error),
+            "regex" => Regex::new(text).map(Pattern::Regex).map_err(error),
+            _ => Err(error(format!("unknown kind `{kind}`"))),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Atom {
+    Literal(char),
+    Any,
+    /// `^`, matching only at the start of the name.
+    Start,
+    /// `$`, matching only at the end of the name.
+    End,
+    Class {
+        ranges: Vec<(char, char)>,
+        negated: bool,
+    },
+    Group(Vec<Vec<Piece>>),
+}
+
+/// An atom repeated between `min` and `max` times.
+#[derive(Debug, Clone)]
+struct Piece {
+    atom: Atom,
+    min: usize,
+    max: usize,
+}
+
+/// Characters with a special meaning somewhere in a regular expression, which are matched
+/// literally when escaped with a backslash.
+const METACHARACTERS: &str = "\\.[]()|*+?^${}-!";
+
+/// A small backtracking regular expression supporting literals, `.`, classes, groups,
+/// alternation, the `*`, `+` and `?` quantifiers and the `^` and `$` anchors.
+#[derive(Debug, Clone)]
+pub struct Regex {
+    alternatives: Vec<Vec<Piece>>,
+}
+
+struct Parser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+impl Parser<'_> {
+    fn alternatives(&mut self) -> Result<Vec<Vec<Piece>>, String> {
+        let mut alternatives = vec![self.sequence()?];
+        while self.chars.next_if_eq(&'|').is_some() {
+            alternatives.push(self.sequence()?);
+        }
+        Ok(alternatives)
+    }
+
+    fn sequence(&mut self) -> Result<Vec<Piece>, String> {
+        let mut pieces = Vec::new();
+        while let Some(&c) = self.chars.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+
+            let atom = self.atom()?;
+            let (min, max) = match self.chars.peek() {
+                Some('*') => (0, usize::MAX),
+                Some('+') => (1, usize::MAX),
+                Some('?') => (0, 1),
+                _ => (1, 1),
+            };
+            if (min, max) != (1, 1) {
+                self.chars.next();
+            }
+            pieces.push(Piece { atom, min, max });
+        }
+        Ok(pieces)
+    }
+
+    fn atom(&mut self) -> Result<Atom, String> {
+        match self.chars.next().unwrap() {
+            '(' => {
+                let alternatives = self.alternatives()?;
+                match self.chars.next() {
+                    Some(')') => Ok(Atom::Group(alternatives)),
+                    _ => Err("unclosed group".to_string()),
+                }
+            }
+            '[' => self.class(),
+            '.' => Ok(Atom::Any),
+            '^' => Ok(Atom::Start),
+            '$' => Ok(Atom::End),
+            '\\' => self.escaped().map(Atom::Literal),
+            c @ ('*' | '+' | '?') => Err(format!("nothing to repeat before `{c}`")),
+            c @ ('{' | '}') => Err(format!("counted repetition `{c}` is not supported")),
+            c => Ok(Atom::Literal(c)),
+        }
+    }
+
+    /// Parses the character after a backslash, which must be one with a special meaning.
+    fn escaped(&mut self) -> Result<char, String> {
+        match self.chars.next() {
+            Some(c) if METACHARACTERS.contains(c) => Ok(c),
+            Some(c) => Err(format!("unsupported escape `\\{c}`")),
+            None => Err("trailing backslash".to_string()),
+        }
+    }
+
+    /// Parses a class after its opening `[`.
+    fn class(&mut self) -> Result<Atom, String> {
+        let negated = self.chars.next_if(|&c| c == '^' || c == '!').is_some();
+        let mut ranges = Vec::new();
+        loop {
+            let start = match self.chars.next() {
+                Some(']') if !ranges.is_empty() => break,
+                Some('\\') => self.escaped()?,
+                Some(c) => c,
+                None => return Err("unclosed class".to_string()),
+            };
+            let end = if self.chars.next_if_eq(&'-').is_some() {
+                match self.chars.next() {
+                    Some(']') => {
+                        ranges.push((start, start));
+                        ranges.push(('-', '-'));
+                        break;
+                    }
+                    Some('\\') => match self.escaped()? {
+                        end if end >= start => end,
+                        end => return Err(format!("empty range `{start}-{end}`")),
+                    },
+                    Some(end) if end >= start => end,
+                    Some(end) => return Err(format!("empty range `{start}-{end}`")),
+                    None => return Err("unclosed class".to_string()),
+                }
+            } else {
+                start
+            };
+            ranges.push((start, end));
+        }
+        Ok(Atom::Class { ranges, negated })
+    }
+}
+
+impl Regex {
+    pub fn new(pattern: &str) -> Result<Self, String> {
+        let mut parser = Parser {
+            chars: pattern.chars().peekable(),
+        };
+        let alternatives = parser.alternatives()?;
+        if let Some(c) = parser.chars.next() {
+            return Err(format!("unexpected `{c}`"));
+        }
+
+        Ok(Self { alternatives })
+    }
+
+    /// Translates a glob into an equivalent anchored regular expression.
+    pub fn glob(glob: &str) -> Result<Self, String> {
+        let piece = |atom| Piece {
+            atom,
+            min: 1,
+            max: 1,
+        };
+        let mut pieces = vec![piece(Atom::Start)];
+        let mut chars = glob.chars().peekable();
+        while let Some(c) = chars.next() {
+            let (atom, min, max) = match c {
+                '*' => (Atom::Any, 0, usize::MAX),
+                '?' => (Atom::Any, 1, 1),
+                '[' => {
+                    let mut parser = Parser { chars };
+                    let class = parser.class()?;
+                    chars = parser.chars;
+                    (class, 1, 1)
+                }
+                c => (Atom::Literal(c), 1, 1),
+            };
+            pieces.push(Piece { atom, min, max });
+        }
+        pieces.push(piece(Atom::End));
+
+        Ok(Self {
+            alternatives: vec![pieces],
+        })
+    }
+
+    pub fn is_match(&self, text: &str) -> bool {
+        let text = text.chars().collect::<Vec<_>>();
+        (0..=text.len())
+            .any(|start| match_alternatives(&self.alternatives, &text, start, &mut |_| true))
+    }
+}
+
+type Continuation<'a> = dyn FnMut(usize) -> bool + 'a;
+
+fn match_alternatives(
+    alternatives: &[Vec<Piece>],
+    text: &[char],
+    pos: usize,
+    k: &mut Continuation,
+) -> bool {
+    alternatives
+        .iter()
+        .any(|sequence| match_sequence(sequence, text, pos, k))
+}
+
+fn match_sequence(sequence: &[Piece], text: &[char], pos: usize, k: &mut Continuation) -> bool {
+    match sequence.split_first() {
+        None => k(pos),
+        Some((piece, rest)) => match_piece(piece, 0, text, pos, &mut |next| {
+            match_sequence(rest, text, next, k)
+        }),
+    }
+}
+
+/// Matches `piece` greedily, having already matched it `count` times.
+fn match_piece(
+    piece: &Piece,
+    count: usize,
+    text: &[char],
+    pos: usize,
+    k: &mut Continuation,
+) -> bool {
+    // Beyond the minimum, repeats that match nothing can't help, and would never end.
+    if count < piece.max
+        && match_atom(&piece.atom, text, pos, &mut |next| {
+            (next != pos || count < piece.min) && match_piece(piece, count + 1, text, next, k)
+        })
+    {
+        return true;
+    }
+    count >= piece.min && k(pos)
+}
+
+fn match_atom(atom: &Atom, text: &[char], pos: usize, k: &mut Continuation) -> bool {
+    let c = text.get(pos);
+    match atom {
+        Atom::Literal(literal) => c == Some(literal) && k(pos + 1),
+        Atom::Any => c.is_some() && k(pos + 1),
+        Atom::Start => pos == 0 && k(pos),
+        Atom::End => pos == text.len() && k(pos),
+        Atom::Class { ranges, negated } => {
+            c.is_some_and(|c| {
+                let contains = ranges.iter().any(|&(start, end)| (start..=end).contains(c));
+                contains != *negated
+            }) && k(pos + 1)
+        }
+        Atom::Group(alternatives) => match_alternatives(alternatives, text, pos, k),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn matches(pattern: &str, names: &[&str]) -> Vec<bool> {
+        let pattern = pattern.parse::<Pattern>().unwrap();
+        names.iter().map(|name| pattern.matches(name)).collect()
+    }
+
+    #[test]
+    fn kinds() {
+        let names = ["AAA", "XAZ", "11A", "ZZZ"];
+        assert_eq!(matches("AAA", &names), [true, false, false, false]);
+        assert_eq!(matches("exact:ZZZ", &names), [false, false, false, true]);
+        assert_eq!(matches("prefix:X", &names), [false, true, false, false]);
+        assert_eq!(matches("suffix:A", &names), [true, false, true, false]);
+        assert_eq!(matches("glob:?A?", &names), [true, true, false, false]);
+        assert_eq!(matches("glob:[0-9]*", &names), [false, false, true, false]);
+        assert_eq!(matches("glob:[!A]*", &names), [false, true, true, true]);
+        assert_eq!(matches("regex:Z$", &names), [false, true, false, true]);
+        assert_eq!(
+            matches("regex:^(AA|1+)A$", &names),
+            [true, false, true, false]
+        );
+    }
+
+    #[test]
+    fn regexes() {
+        let regex = |pattern| Regex::new(pattern).unwrap();
+
+        assert!(regex("A.Z").is_match("XAQZ"));
+        assert!(!regex("^A.Z").is_match("XAQZ"));
+        assert!(regex("^[A-C]+Z?$").is_match("ABCAZ"));
+        assert!(!regex("^[A-C]+Z?$").is_match("ABCD"));
+        assert!(regex("^(A|B)*C$").is_match("C"));
+        assert!(regex("^(A*)*$").is_match("AAA"));
+        assert!(regex("^[^0-9]+$").is_match("ABC"));
+        assert!(!regex("^[^0-9]+$").is_match("A1C"));
+        assert!(regex("^a\\.b$").is_match("a.b"));
+        assert!(!regex("^a\\.b$").is_match("axb"));
+        assert!(regex("").is_match("anything"));
+        assert!(regex("^a\\$$").is_match("a$"));
+        assert!(regex("^[\\]\\-]+$").is_match("]-]"));
+
+        // Anchors belong to each alternative, and can appear anywhere.
+        assert!(regex("^AAA|^ZZZ").is_match("ZZZ"));
+        assert!(!regex("^AAA|^ZZZ").is_match("XZZZ"));
+        assert!(regex("AAA$|ZZZ$").is_match("AAA"));
+        assert!(!regex("AAA$|ZZZ$").is_match("AAAX"));
+        assert!(regex("(^A|B)C").is_match("AC"));
+        assert!(!regex("(^A|B)C").is_match("XAC"));
+        assert!(regex("A(B|$)").is_match("XA"));
+        assert!(!regex("A^").is_match("A"));
+        assert!(regex("^+A").is_match("A"));
+    }
+
+    #[test]
+    fn errors() {
+        for pattern in [
+            "regex:(AB",
+            "regex:AB)",
+            "regex:*A",
+            "regex:[Z-A]",
+            "regex:\\d",
+            "regex:A{2}",
+            "regex:A}",
+            "regex:[\\w]",
+            "regex:A\\",
+            "glob:[AB",
+            "fuzzy:A",
+        ] {
+            let error = pattern.parse::<Pattern>().unwrap_err();
+            assert_eq!(error.pattern, pattern);
+        }
+
+        let message = |pattern: &str| pattern.parse::<Pattern>().unwrap_err().message;
+        assert_eq!(message("regex:\\d"), "unsupported escape `\\d`");
+        assert_eq!(
+            message("regex:A{2}"),
+            "counted repetition `{` is not supported"
+        );
+    }
+}
@@ -0,0 +1,134 @@
+//! Walks every ghost through the network together, one step at a time.
+
+use crate::network::Network;
+
+#[derive(Debug, Copy, Clone)]
+struct Ghost {
+    node: usize,
+    /// The index of the next instruction this ghost will follow.
+    cursor: usize,
+}
+
+pub struct Simulator<'a> {
+    network: &'a Network,
+    ghosts: Vec<Ghost>,
+    steps: u64,
+}
+
+impl<'a> Simulator<'a> {
+    pub fn new(starts: impl IntoIterator<Item = usize>, network: &'a Network) -> Self {
+        Self {
+            network,
+            ghosts: starts
+                .into_iter()
+                .map(|node| Ghost { node, cursor: 0 })
+                .collect(),
+            steps: 0,
+        }
+    }
+
+    /// Moves every ghost along its next instruction.
+    pub fn step(&mut self) {
+        for ghost in &mut self.ghosts {
+            ghost.node = self.network.next(ghost.node, ghost.cursor);
+            ghost.cursor = (ghost.cursor + 1) % self.network.instructions();
+        }
+        self.steps += 1;
+    }
+
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.ghosts
+            .iter()
+            .map(|ghost| self.network.name(ghost.node))
+    }
+
+    pub fn all_at_end(&self, is_goal: impl Fn(&str) -> bool) -> bool {
+        self.positions().all(is_goal)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rng::Rng;
+    use crate::{load, part_2, Number};
+
+    fn brute_force(input: &'static str, limit: u64) -> Option<u64> {
+        let network = load(input).unwrap();
+        let starts = (0..network.len()).filter(|&node| network.name(node).ends_with('A'));
+        let mut simulator = Simulator::new(starts, &network);
+
+        while simulator.steps() < limit {
+            simulator.step();
+            if simulator.all_at_end(|node| node.ends_with('Z')) {
+                return Some(simulator.steps());
+            }
+        }
+        None
+    }
+
+    #[test]
+    fn example() {
+        let network = load(include_str!("../ghosts.txt")).unwrap();
+        let starts = ["11A", "22A"].map(|name| network.index(name).unwrap());
+        let mut simulator = Simulator::new(starts, &network);
+        let is_goal = |node: &str| node.ends_with('Z');
+
+        simulator.step();
+        assert_eq!(simulator.positions().collect::<Vec<_>>(), ["11B", "22B"]);
+        simulator.step();
+        assert_eq!(simulator.positions().collect::<Vec<_>>(), ["11Z", "22C"]);
+        assert!(!simulator.all_at_end(is_goal));
+        for _ in 0..4 {
+            simulator.step();
+        }
+        assert_eq!(simulator.positions().collect::<Vec<_>>(), ["11Z", "22Z"]);
+        assert!(simulator.all_at_end(is_goal));
+
+        assert_eq!(brute_force(include_str!("../ghosts.txt"), 100), Some(6));
+    }
+
+    #[test]
+    fn independent_cursors() {
+        // A ghost that reaches its goal early must not move the other ghosts' instructions on.
+        let input = "LRR\n\n\
+            1A = (1Z, 1Z)\n1Z = (1Z, 1Z)\n\
+            2A = (2B, 2X)\n2B = (2X, 2Z)\n2Z = (2Z, 2Z)\n2X = (2X, 2X)\n";
+        assert_eq!(brute_force(input, 100), Some(2));
+        assert_eq!(part_2(&load(input).unwrap()), Some(Number::from(2)));
+    }
+
+    #[test]
+    fn matches_cycle_analysis() {
+        let mut rng = Rng::new(0x2023_1208);
+        let mut below = |n: u64| rng.below(n);
+
+        for _ in 0..500 {
+            let nodes = 2 + below(7);
+            let name = |i: u64| {
+                let suffix = ["A", "B", "Z"][(i % 3) as usize];
+                format!("{i}{suffix}")
+            };
+
+            let mut input = (0..1 + below(4))
+                .map(|_| if below(2) == 0 { 'L' } else { 'R' })
+                .collect::<String>();
+            input.push_str("\n\n");
+            for i in 0..nodes {
+                let (left, right) = (name(below(nodes)), name(below(nodes)));
+                input.push_str(&format!("{} = ({left}, {right})\n", name(i)));
+            }
+            let input: &'static str = Box::leak(input.into_boxed_str());
+
+            let expected = part_2(&load(input).unwrap()).and_then(|steps| match steps {
+                Number::Small(steps) if steps <= 5_000 => Some(steps),
+                _ => None,
+            });
+            assert_eq!(brute_force(input, 5_000), expected, "{input}");
+        }
+    }
+}
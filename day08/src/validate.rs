@@ -0,0 +1,272 @@
+//! Checks a network for mistakes that would otherwise only show up as a panic or a walk that
+//! never ends.
+
+use std::{collections::HashMap, fmt};
+
+use crate::graph::reachable;
+use crate::pattern::Pattern;
+use crate::{definitions, instructions, load, ParseError};
+
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Problem {
+    EmptyInstructions,
+    InvalidDirection {
+        column: usize,
+        found: char,
+    },
+    MissingBlankLine,
+    MalformedDefinition {
+        line: usize,
+    },
+    DuplicateNode {
+        node: &'static str,
+        line: usize,
+        first: usize,
+    },
+    UndefinedNode {
+        node: &'static str,
+        line: usize,
+    },
+    /// No goal can be reached from `start`, whatever the instructions.
+    UnreachableGoals {
+        start: &'static str,
+    },
+    /// A node reachable from a start that isn't a goal and only leads back to itself.
+    StuckSelfLoop {
+        node: &'static str,
+    },
+}
+
+impl Problem {
+    /// Whether the network can't be walked at all. Other problems only mean that some walks
+    /// never reach a goal.
+    pub fn is_error(&self) -> bool {
+        !matches!(
+            self,
+            Problem::UnreachableGoals { .. } | Problem::StuckSelfLoop { .. }
+        )
+    }
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Problem::EmptyInstructions => write!(f, "{}", ParseError::EmptyInstructions),
+            &Problem::InvalidDirection { column, found } => {
+                write!(f, "{}", ParseError::InvalidDirection { column, found })
+            }
+            Problem::MissingBlankLine => write!(f, "{}", ParseError::MissingBlankLine),
+            &Problem::MalformedDefinition { line } => {
+                write!(f, "{}", ParseError::MalformedDefinition { line })
+            }
+            Problem::DuplicateNode { node, line, first } => {
+                write!(f, "line {line}: {node} is already defined on line {first}")
+            }
+            Problem::UndefinedNode { node, line } => {
+                write!(f, "line {line}: {node} is never defined")
+            }
+            Problem::UnreachableGoals { start } => write!(f, "no goal can be reached from {start}"),
+            Problem::StuckSelfLoop { node } => write!(
+                f,
+                "{node} only leads back to itself, so a walk that reaches it never gets further"
+            ),
+        }
+    }
+}
+
+impl From<ParseError> for Problem {
+    fn from(error: ParseError) -> Self {
+        match error {
+            ParseError::EmptyInstructions => Problem::EmptyInstructions,
+            ParseError::InvalidDirection { column, found } => {
+                Problem::InvalidDirection { column, found }
+            }
+            ParseError::MissingBlankLine => Problem::MissingBlankLine,
+            ParseError::MalformedDefinition { line } => Problem::MalformedDefinition { line },
+        }
+    }
+}
+
+/// Checks the instructions and the network's definitions, and then each walk from its starts to its goals.
+pub fn validate(input: &'static str, walks: &[(&Pattern, &Pattern)]) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    if let Err(error) = instructions(input) {
+        problems.push(error.into());
+    }
+
+    let mut defined = HashMap::new();
+    for definition in definitions(input) {
+        let (line, name, _) = match definition {
+            Ok(definition) => definition,
+            Err(error) => {
+                problems.push(error.into());
+                continue;
+            }
+        };
+        if let Some(&first) = defined.get(name) {
+            problems.push(Problem::DuplicateNode {
+                node: name,
+                line,
+                first,
+            });
+        } else {
+            defined.insert(name, line);
+        }
+    }
+    for (line, _, node) in definitions(input).flatten() {
+        for reference in [node.left, node.right] {
+            if !defined.contains_key(reference) {
+                problems.push(Problem::UndefinedNode {
+                    node: reference,
+                    line,
+                });
+            }
+        }
+    }
+
+    // The network can only be compiled when it parses and every reference is defined.
+    let network = problems
+        .iter()
+        .all(|problem| !problem.is_error())
+        .then(|| load(input))
+        .and_then(Result::ok);
+    if let Some(network) = network {
+        for &(starts, goals) in walks {
+            let goals = network.select(|node| goals.matches(node));
+            for start in network.matching(|node| starts.matches(node)) {
+                let reachable = reachable(&network, start);
+                if !reachable.iter().any(|&node| goals[node]) {
+                    problems.push(Problem::UnreachableGoals {
+                        start: network.name(start),
+                    });
+                }
+                problems.extend(
+                    reachable
+                        .into_iter()
+                        .filter(|&node| !goals[node] && network.neighbours(node) == [node; 2])
+                        .map(|node| Problem::StuckSelfLoop {
+                            node: network.name(node),
+                        }),
+                );
+            }
+        }
+    }
+
+    problems.sort();
+    problems.dedup();
+    problems
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn walk(starts: &str, goals: &str) -> (Pattern, Pattern) {
+        (starts.parse().unwrap(), goals.parse().unwrap())
+    }
+
+    #[test]
+    fn examples() {
+        let (starts, goals) = walk("AAA", "ZZZ");
+        assert_eq!(
+            validate(include_str!("../example2.txt"), &[(&starts, &goals)]),
+            []
+        );
+
+        // The instructions happen to avoid them, but the dead ends are still reachable.
+        assert_eq!(
+            validate(include_str!("../example.txt"), &[(&starts, &goals)]),
+            ["DDD", "EEE", "GGG"].map(|node| Problem::StuckSelfLoop { node })
+        );
+
+        // Ghosts that wander off to XXX are stuck there.
+        let (starts, goals) = walk("suffix:A", "suffix:Z");
+        assert_eq!(
+            validate(include_str!("../ghosts.txt"), &[(&starts, &goals)]),
+            [Problem::StuckSelfLoop { node: "XXX" }]
+        );
+    }
+
+    #[test]
+    fn problems() {
+        let input = "LR\n\n\
+            AAA = (BBB, CCC)\n\
+            BBB = (BBB, BBB)\n\
+            CCC = (BBB, DDD)\n\
+            AAA = (ZZZ, ZZZ)\n\
+            ZZZ = (ZZZ, ZZZ)\n";
+        let (starts, goals) = walk("AAA", "ZZZ");
+
+        assert_eq!(
+            validate(input, &[(&starts, &goals)]),
+            [
+                Problem::DuplicateNode {
+                    node: "AAA",
+                    line: 6,
+                    first: 3
+                },
+                Problem::UndefinedNode {
+                    node: "DDD",
+                    line: 5
+                },
+            ]
+        );
+
+        let input = input
+            .replace("DDD", "AAA")
+            .replace("AAA = (ZZZ, ZZZ)\n", "");
+        let input: &'static str = Box::leak(input.into_boxed_str());
+        let problems = validate(input, &[(&starts, &goals)]);
+        assert_eq!(
+            problems,
+            [
+                Problem::UnreachableGoals { start: "AAA" },
+                Problem::StuckSelfLoop { node: "BBB" },
+            ]
+        );
+        assert!(problems.iter().all(|problem| !problem.is_error()));
+    }
+
+    #[test]
+    fn malformed_input() {
+        let (starts, goals) = walk("AAA", "ZZZ");
+        let input = "LXR\n\n\
+            AAA = (ZZZ, ZZZ)\n\
+            BBB = ZZZ, ZZZ\n\
+            ZZZ (ZZZ, ZZZ)\n";
+        let problems = validate(input, &[(&starts, &goals)]);
+
+        assert_eq!(
+            problems,
+            [
+                Problem::InvalidDirection {
+                    column: 2,
+                    found: 'X'
+                },
+                Problem::MalformedDefinition { line: 4 },
+                Problem::MalformedDefinition { line: 5 },
+                Problem::UndefinedNode {
+                    node: "ZZZ",
+                    line: 3
+                },
+            ]
+        );
+        assert!(problems.iter().all(Problem::is_error));
+        assert_eq!(
+            problems[1].to_string(),
+            "line 4: expected a node like `AAA = (BBB, CCC)`"
+        );
+
+        assert_eq!(
+            validate("\n\nAAA = (AAA, AAA)\n", &[(&starts, &goals)]),
+            [Problem::EmptyInstructions]
+        );
+        assert_eq!(
+            validate(
+                "LR\nAAA = (ZZZ, ZZZ)\nZZZ = (ZZZ, ZZZ)\n",
+                &[(&starts, &goals)]
+            ),
+            [Problem::MissingBlankLine]
+        );
+    }
+}
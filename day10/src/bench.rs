@@ -0,0 +1,143 @@
+//! Times both parts on generated sketches far larger than the puzzle input.
+
+use std::time::Instant;
+
+use crate::{area_by_shoelace, area_enclosed_by_loop, distances_from_start, parse_tiles};
+
+/// Picks the junk pipes and where the start goes, seeded so a benchmark can be rerun exactly.
+pub struct Rng(pub u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+}
+
+/// The points of a grid `size` points wide and high (where `size` is even) in the order a loop
+/// visits them: along the top row, snaking back and forth across the other rows, then back up
+/// the first column.
+fn snake(size: i32) -> Vec<(i32, i32)> {
+    let mut points = (0..size).map(|x| (x, 0)).collect::<Vec<_>>();
+    for y in 1..size {
+        if y % 2 == 1 {
+            points.extend((1..size).rev().map(|x| (x, y)));
+        } else {
+            points.extend((1..size).map(|x| (x, y)));
+        }
+    }
+    points.extend((1..size).rev().map(|y| (0, y)));
+    points
+}
+
+/// Generates a sketch about `size` tiles wide and high, whose loop snakes across it with gaps
+/// between its rows. Tiles off the loop are random pipes or ground.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let points = (size as i32 - 1) / 2 / 2 * 2;
+    let points = points.max(2);
+    let width = 2 * points as usize + 1;
+
+    // Spread the points out with a border around them, and join them up.
+    let cells = snake(points)
+        .into_iter()
+        .map(|(x, y)| (2 * x + 1, 2 * y + 1))
+        .collect::<Vec<_>>();
+    let cells = cells
+        .iter()
+        .zip(cells.iter().cycle().skip(1))
+        .flat_map(|(&(x, y), &(nx, ny))| [(x, y), ((x + nx) / 2, (y + ny) / 2)])
+        .collect::<Vec<_>>();
+
+    const PIPES: [char; 7] = ['|', '-', 'L', 'J', '7', 'F', '.'];
+    let mut grid = (0..width)
+        .map(|_| {
+            (0..width)
+                .map(|_| PIPES[rng.below(PIPES.len() as u64) as usize])
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    for (i, &(x, y)) in cells.iter().enumerate() {
+        let (px, py) = cells[(i + cells.len() - 1) % cells.len()];
+        let (nx, ny) = cells[(i + 1) % cells.len()];
+        let mut directions = [(px - x, py - y), (nx - x, ny - y)];
+        directions.sort_unstable();
+
+        grid[y as usize][x as usize] = match directions {
+            [(0, -1), (0, 1)] => '|',
+            [(-1, 0), (1, 0)] => '-',
+            [(0, -1), (1, 0)] => 'L',
+            [(-1, 0), (0, -1)] => 'J',
+            [(-1, 0), (0, 1)] => '7',
+            [(0, 1), (1, 0)] => 'F',
+            _ => unreachable!("{directions:?}"),
+        };
+    }
+
+    // Clear the junk around the start, so that only its two pipes connect to it.
+    let (x, y) = cells[rng.below(cells.len() as u64) as usize];
+    for (dx, dy) in [(0, -1), (0, 1), (-1, 0), (1, 0)] {
+        let (nx, ny) = (x + dx, y + dy);
+        if !cells.contains(&(nx, ny)) {
+            grid[ny as usize][nx as usize] = '.';
+        }
+    }
+    grid[y as usize][x as usize] = 'S';
+
+    grid.into_iter()
+        .map(|row| row.into_iter().chain(['\n']).collect::<String>())
+        .collect()
+}
+
+fn time<T>(name: &str, f: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let result = f();
+    println!("  {name:<8} {:>10.1?}", start.elapsed());
+    result
+}
+
+pub fn run(size: usize) {
+    let input = generate(&mut Rng(0x2023_1210), size);
+    let input: &'static str = Box::leak(input.into_boxed_str());
+
+    let sketch = time("parse", || parse_tiles(input)).unwrap();
+    println!("{} by {} tiles", sketch.width, sketch.height);
+
+    let pipe_loop = sketch.pipe_loop();
+    let pipes = time("set", || pipe_loop.iter().copied().collect());
+    let distance = time("part 1", || distances_from_start(&pipes));
+    let area = time("part 2", || area_enclosed_by_loop(&sketch, &pipes));
+    let shoelace = time("shoelace", || area_by_shoelace(pipe_loop));
+    assert_eq!(area, shoelace, "The enclosed areas disagree");
+    println!("  farthest {distance}, enclosed {area}");
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn generated_loops() {
+        let mut rng = Rng(10);
+        for size in [5, 9, 20, 51] {
+            let input = Box::leak(generate(&mut rng, size).into_boxed_str());
+            let sketch = parse_tiles(input).unwrap();
+            let points = ((size - 1) / 4 * 2).max(2);
+
+            let pipes = sketch.tiles_in_pipe_loop();
+
+            assert_eq!(sketch.width, 2 * points + 1);
+            assert_eq!(distances_from_start(&pipes), points * points);
+            assert_eq!(
+                area_by_shoelace(sketch.pipe_loop()),
+                area_enclosed_by_loop(&sketch, &pipes)
+            );
+        }
+    }
+}
@@ -1,4 +1,6 @@
-use std::collections::{HashSet, VecDeque};
+mod bench;
+
+use std::{collections::HashSet, fmt};
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 enum TileType {
@@ -24,13 +26,108 @@ struct Tile {
     position: Position,
 }
 
+/// The tiles of a sketch, row by row. The start tile holds the pipe it hides, rather than
+/// `TileType::Start`.
 #[derive(Debug, Clone)]
 struct Sketch {
+    width: usize,
+    height: usize,
+    start: Position,
     tiles: Vec<Tile>,
+    /// The tiles of the loop in the order they are passed walking round it, beginning at the
+    /// start.
+    pipe_loop: Vec<Tile>,
+}
+
+/// Why the pipe under the start tile couldn't be worked out.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum StartError {
+    Missing,
+    /// More than two neighbours connect to the start, so it could be more than one pipe.
+    Ambiguous {
+        connections: usize,
+    },
+    /// Fewer than two neighbours connect to the start, so it can't be part of a loop.
+    Unconnected {
+        connections: usize,
+    },
+}
+
+impl fmt::Display for StartError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StartError::Missing => write!(f, "the sketch has no start tile"),
+            StartError::Ambiguous { connections } => write!(
+                f,
+                "{connections} pipes connect to the start, so its shape is ambiguous"
+            ),
+            StartError::Unconnected { connections } => write!(
+                f,
+                "only {connections} pipes connect to the start, but a loop needs two"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for StartError {}
+
+/// Why a sketch couldn't be parsed. Lines and columns start from one.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum ParseError {
+    InvalidTile {
+        line: usize,
+        column: usize,
+        found: char,
+    },
+    /// A row isn't as wide as the first.
+    RaggedRow {
+        line: usize,
+        width: usize,
+        expected: usize,
+    },
+    Start(StartError),
+    /// Walking round the loop from the start leads to a tile with no pipe connecting back.
+    BrokenLoop {
+        line: usize,
+        column: usize,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::InvalidTile {
+                line,
+                column,
+                found,
+            } => write!(f, "line {line}, column {column}: `{found}` is not a tile"),
+            ParseError::RaggedRow {
+                line,
+                width,
+                expected,
+            } => write!(
+                f,
+                "line {line}: the row is {width} tiles wide, but the first row is {expected}"
+            ),
+            ParseError::Start(error) => write!(f, "{error}"),
+            ParseError::BrokenLoop { line, column } => write!(
+                f,
+                "line {line}, column {column}: the loop from the start is broken here"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<StartError> for ParseError {
+    fn from(error: StartError) -> Self {
+        ParseError::Start(error)
+    }
 }
 
-fn parse_tile(c: char) -> TileType {
-    match c {
+fn parse_tile(c: char) -> Option<TileType> {
+    Some(match c {
         '|' => TileType::Vertical,
         '-' => TileType::Horizontal,
         'L' => TileType::NorthToEastBend,
@@ -39,26 +136,67 @@ fn parse_tile(c: char) -> TileType {
         'F' => TileType::SouthToEastBend,
         'S' => TileType::Start,
         '.' => TileType::Ground,
-        _ => panic!("Invalid tile"),
-    }
+        _ => return None,
+    })
 }
 
-fn parse_tiles(input: &'static str) -> Sketch {
-    let tiles = input
-        .lines()
-        .enumerate()
-        .flat_map(|(y, line)| {
-            line.chars().enumerate().map(move |(x, char)| Tile {
-                tile_type: parse_tile(char),
+fn parse_tiles(input: &'static str) -> Result<Sketch, ParseError> {
+    let mut tiles = Vec::new();
+    let mut width = None;
+
+    for (y, line) in input.lines().enumerate() {
+        let row_start = tiles.len();
+        for (x, char) in line.chars().enumerate() {
+            let tile_type = parse_tile(char).ok_or(ParseError::InvalidTile {
+                line: y + 1,
+                column: x + 1,
+                found: char,
+            })?;
+            tiles.push(Tile {
+                tile_type,
                 position: Position {
                     x: x as i32,
                     y: y as i32,
                 },
-            })
-        })
-        .collect();
+            });
+        }
 
-    Sketch { tiles }
+        let row = tiles.len() - row_start;
+        match width {
+            None => width = Some(row),
+            Some(expected) if row != expected => {
+                return Err(ParseError::RaggedRow {
+                    line: y + 1,
+                    width: row,
+                    expected,
+                })
+            }
+            Some(_) => {}
+        }
+    }
+    let width = width.unwrap_or(0);
+    let height = input.lines().count();
+
+    let start = tiles
+        .iter()
+        .find(|tile| tile.tile_type == TileType::Start)
+        .ok_or(StartError::Missing)?
+        .position;
+
+    let mut sketch = Sketch {
+        width,
+        height,
+        start,
+        tiles,
+        pipe_loop: Vec::new(),
+    };
+    let tile_type = sketch.start_shape()?;
+    sketch.tiles[start.y as usize * width + start.x as usize].tile_type = tile_type;
+    sketch.pipe_loop = sketch.walk_loop().map_err(|tile| ParseError::BrokenLoop {
+        line: tile.position.y as usize + 1,
+        column: tile.position.x as usize + 1,
+    })?;
+    Ok(sketch)
 }
 
 impl Position {
@@ -130,13 +268,8 @@ impl Tile {
                 None,
                 None,
             ],
-            TileType::Start => [
-                Some(self.position.up()),
-                Some(self.position.down()),
-                Some(self.position.left()),
-                Some(self.position.right()),
-            ],
-            TileType::Ground => [None, None, None, None],
+            // The start's shape is inferred from its neighbours when parsing.
+            TileType::Start | TileType::Ground => [None, None, None, None],
         }
         .into_iter()
         .flatten()
@@ -145,15 +278,41 @@ impl Tile {
 
 impl Sketch {
     fn start(&self) -> Tile {
-        self.tiles
-            .iter()
-            .find(|tile| tile.tile_type == TileType::Start)
-            .copied()
-            .unwrap()
+        self.get(self.start).unwrap()
+    }
+
+    /// The pipe under the start tile, from which of its neighbours connect back to it.
+    fn start_shape(&self) -> Result<TileType, StartError> {
+        let [up, down, left, right] = [
+            self.start.up(),
+            self.start.down(),
+            self.start.left(),
+            self.start.right(),
+        ]
+        .map(|pos| {
+            self.get(pos)
+                .is_some_and(|tile| tile.connections().any(|pos| pos == self.start))
+        });
+
+        let connections = [up, down, left, right].into_iter().filter(|&c| c).count();
+        match connections {
+            2 => Ok(match (up, down, left, right) {
+                (true, true, _, _) => TileType::Vertical,
+                (_, _, true, true) => TileType::Horizontal,
+                (true, _, _, true) => TileType::NorthToEastBend,
+                (true, _, true, _) => TileType::NorthToWestBend,
+                (_, true, true, _) => TileType::SouthToWestBend,
+                _ => TileType::SouthToEastBend,
+            }),
+            0 | 1 => Err(StartError::Unconnected { connections }),
+            _ => Err(StartError::Ambiguous { connections }),
+        }
     }
 
     fn get(&self, pos: Position) -> Option<Tile> {
-        self.tiles.iter().find(|tile| tile.position == pos).copied()
+        let x = usize::try_from(pos.x).ok().filter(|&x| x < self.width)?;
+        let y = usize::try_from(pos.y).ok().filter(|&y| y < self.height)?;
+        Some(self.tiles[y * self.width + x])
     }
 
     fn is_connected(&self, src: Tile, dst: Tile) -> bool {
@@ -165,27 +324,34 @@ impl Sketch {
         tile.connections().filter_map(|pos| self.get(pos))
     }
 
-    fn tiles_in_pipe_loop(&self) -> HashSet<Tile> {
+    /// Walks round the loop from the start, or returns the last tile reached if the loop is
+    /// broken there.
+    fn walk_loop(&self) -> Result<Vec<Tile>, Tile> {
         let start = self.start();
-        let mut to_visit = self
-            .connections(start)
-            .filter(|&tile| self.is_connected(start, tile))
-            .collect::<VecDeque<_>>();
-        assert_eq!(to_visit.len(), 2);
-
-        let mut visited = HashSet::from([start]);
-
-        while let Some(current) = to_visit.pop_front() {
-            visited.insert(current);
-            for next in self
+        let mut pipes = vec![start];
+        let mut previous = start;
+        // The start's shape was chosen from the neighbours that connect back to it.
+        let mut current = self.connections(start).next().ok_or(start)?;
+
+        while current != start {
+            pipes.push(current);
+            let next = self
                 .connections(current)
-                .filter(|tile| !visited.contains(tile) && self.is_connected(current, *tile))
-            {
-                to_visit.push_back(next);
-            }
+                .find(|&tile| tile != previous && self.is_connected(current, tile))
+                .ok_or(current)?;
+            previous = current;
+            current = next;
         }
 
-        visited
+        Ok(pipes)
+    }
+
+    fn pipe_loop(&self) -> &[Tile] {
+        &self.pipe_loop
+    }
+
+    fn tiles_in_pipe_loop(&self) -> HashSet<Tile> {
+        self.pipe_loop.iter().copied().collect()
     }
 }
 
@@ -216,53 +382,216 @@ fn area_enclosed_by_loop(sketch: &Sketch, pipes: &HashSet<Tile>) -> i32 {
     count
 }
 
+/// The number of tiles enclosed by the loop, from the area of the polygon through the centres of
+/// its tiles (the Shoelace formula) and Pick's theorem, which relates that area to the tiles
+/// inside and on the loop.
+fn area_by_shoelace(pipe_loop: &[Tile]) -> i32 {
+    let twice_area = pipe_loop
+        .iter()
+        .zip(pipe_loop.iter().cycle().skip(1))
+        .map(|(a, b)| {
+            let (a, b) = (a.position, b.position);
+            i64::from(a.x) * i64::from(b.y) - i64::from(b.x) * i64::from(a.y)
+        })
+        .sum::<i64>()
+        .abs();
+
+    let boundary = pipe_loop.len() as i64;
+    i32::try_from((twice_area - boundary) / 2 + 1).unwrap()
+}
+
 fn main() {
-    let sketch = parse_tiles(include_str!("input.txt"));
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    if args.first().map(String::as_str) == Some("bench") {
+        let size = args.get(1).map_or(1000, |size| size.parse().unwrap());
+        bench::run(size);
+        return;
+    }
+
+    let sketch = or_exit(parse_tiles(include_str!("input.txt")));
     let pipe_tiles = sketch.tiles_in_pipe_loop();
 
     println!("Part 1: {:?}", distances_from_start(&pipe_tiles));
     println!("Part 2: {:?}", area_enclosed_by_loop(&sketch, &pipe_tiles));
 }
 
+fn or_exit<T, E: fmt::Display>(result: Result<T, E>) -> T {
+    result.unwrap_or_else(|error| {
+        eprintln!("{error}");
+        std::process::exit(1);
+    })
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
-    #[test]
-    fn example() {
-        const INPUT: &'static str = r#".....
+    const EXAMPLE: &str = r#".....
 .S-7.
 .|.|.
 .L-J.
 ....."#;
-        let sketch = parse_tiles(INPUT);
-        assert_eq!(distances_from_start(&sketch.tiles_in_pipe_loop()), 4);
-    }
 
-    #[test]
-    fn complex_example() {
-        const INPUT: &'static str = r#"..F7.
+    const COMPLEX_EXAMPLE: &str = r#"..F7.
 .FJ|.
 SJ.L7
 |F--J
 LJ..."#;
-        let sketch = parse_tiles(INPUT);
+
+    const ENCLOSED: &str = r#"...........
+.S-------7.
+.|F-----7|.
+.||.....||.
+.||.....||.
+.|L-7.F-J|.
+.|..|.|..|.
+.L--J.L--J.
+..........."#;
+
+    #[test]
+    fn example() {
+        let sketch = parse_tiles(EXAMPLE).unwrap();
+        assert_eq!(distances_from_start(&sketch.tiles_in_pipe_loop()), 4);
+    }
+
+    #[test]
+    fn complex_example() {
+        let sketch = parse_tiles(COMPLEX_EXAMPLE).unwrap();
         assert_eq!(distances_from_start(&sketch.tiles_in_pipe_loop()), 8);
     }
 
     #[test]
     fn enclosed() {
-        const INPUT: &'static str = r#"...........
-.S-------7.
+        let sketch = parse_tiles(ENCLOSED).unwrap();
+        assert_eq!(
+            area_enclosed_by_loop(&sketch, &sketch.tiles_in_pipe_loop()),
+            4
+        );
+    }
+
+    #[test]
+    fn shoelace() {
+        let examples = [
+            (EXAMPLE, 1),
+            (COMPLEX_EXAMPLE, 1),
+            (ENCLOSED, 4),
+            (
+                r#".F----7F7F7F7F-7....
+.|F--7||||||||FJ....
+.||.FJ||||||||L7....
+FJL7L7LJLJ||LJ.L-7..
+L--J.L7...LJS7F-7L7.
+....F-J..F7FJ|L7L7L7
+....L7.F7||L7|.L7L7|
+.....|FJLJ|FJ|F7|.LJ
+....FJL-7.||.||||...
+....L---J.LJ.LJLJ..."#,
+                8,
+            ),
+            (
+                r#"FF7FSF7F7F7F7F7F---7
+L|LJ||||||||||||F--J
+FL-7LJLJ||||||LJL-77
+F--JF--7||LJLJ7F7FJ-
+L---JF-JLJ.||-FJLJJ7
+|F|F-JF---7F7-L7L|7|
+|FFJF7L7F-JF7|JL---7
+7-L-JL7||F7|L7F-7F7|
+L.L7LFJ|||||FJL7||LJ
+L7JLJL-JLJLJL--JLJ.L"#,
+                10,
+            ),
+        ];
+
+        for (input, enclosed) in examples {
+            let sketch = parse_tiles(input).unwrap();
+            let pipe_loop = sketch.pipe_loop();
+            assert_eq!(pipe_loop[0], sketch.start());
+            assert_eq!(
+                area_enclosed_by_loop(&sketch, &sketch.tiles_in_pipe_loop()),
+                enclosed
+            );
+            assert_eq!(area_by_shoelace(pipe_loop), enclosed);
+        }
+    }
+
+    #[test]
+    fn out_of_bounds() {
+        let sketch = parse_tiles("S7\nLJ").unwrap();
+        assert_eq!((sketch.width, sketch.height), (2, 2));
+        assert_eq!(
+            sketch
+                .get(Position { x: 1, y: 1 })
+                .map(|tile| tile.tile_type),
+            Some(TileType::NorthToWestBend)
+        );
+        for (x, y) in [(-1, 0), (0, -1), (2, 0), (0, 2)] {
+            assert_eq!(sketch.get(Position { x, y }), None);
+        }
+    }
+
+    #[test]
+    fn start_shapes() {
+        let shape = |input| parse_tiles(input).map(|sketch| sketch.start().tile_type);
+
+        assert_eq!(shape("S7\nLJ"), Ok(TileType::SouthToEastBend));
+        assert_eq!(shape("F7\nLS"), Ok(TileType::NorthToWestBend));
+        assert_eq!(shape("F-7\nS.|\nL-J"), Ok(TileType::Vertical));
+        assert_eq!(shape("F7.\n|L7\nL-S"), Ok(TileType::NorthToWestBend));
+        assert_eq!(shape("F-S-7\n|...|\nL---J"), Ok(TileType::Horizontal));
+
+        assert_eq!(shape("..\n.."), Err(StartError::Missing.into()));
+        assert_eq!(
+            shape("S-\n.."),
+            Err(StartError::Unconnected { connections: 1 }.into())
+        );
+        assert_eq!(
+            shape("-S-\n.|."),
+            Err(StartError::Ambiguous { connections: 3 }.into())
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error = |input| parse_tiles(input).unwrap_err();
+
+        assert_eq!(
+            error("S7\nLX"),
+            ParseError::InvalidTile {
+                line: 2,
+                column: 2,
+                found: 'X'
+            }
+        );
+        assert_eq!(
+            error("S-7\n|.|\nL-").to_string(),
+            "line 3: the row is 2 tiles wide, but the first row is 3"
+        );
+        assert_eq!(
+            error("S-\n..").to_string(),
+            "only 1 pipes connect to the start, but a loop needs two"
+        );
+        assert_eq!(
+            error("F-S-7\n|...|\nL---."),
+            ParseError::BrokenLoop { line: 3, column: 4 }
+        );
+    }
+
+    #[test]
+    fn enclosed_with_start_on_a_bend() {
+        // The start hides a J, which doesn't connect up to the row above.
+        const INPUT: &str = r#"...........
+.F-------7.
 .|F-----7|.
 .||.....||.
 .||.....||.
-.|L-7.F-J|.
+.|L-7.F-S|.
 .|..|.|..|.
 .L--J.L--J.
 ..........."#;
 
-        let sketch = parse_tiles(INPUT);
+        let sketch = parse_tiles(INPUT).unwrap();
+        assert_eq!(sketch.start().tile_type, TileType::NorthToWestBend);
         assert_eq!(
             area_enclosed_by_loop(&sketch, &sketch.tiles_in_pipe_loop()),
             4